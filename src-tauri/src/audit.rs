@@ -0,0 +1,36 @@
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqliteConnection;
+
+use crate::error::CommandError;
+
+/// Appends an entry to the `audit_log` table.
+///
+/// Call it on the same transaction as the write it describes, so that both are
+/// committed or rolled back together. `before` is `None` for inserts and
+/// `after` is `None` for deletions.
+pub async fn record(
+    conn: &mut SqliteConnection,
+    command: &str,
+    entity: &str,
+    entity_id: i64,
+    before: Option<Value>,
+    after: Option<Value>,
+) -> Result<(), CommandError> {
+    sqlx::query(
+        "INSERT INTO audit_log (command, entity, entity_id, before_json, after_json) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(command)
+    .bind(entity)
+    .bind(entity_id)
+    .bind(before.map(|v| v.to_string()))
+    .bind(after.map(|v| v.to_string()))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub fn snapshot<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
@@ -1,18 +1,38 @@
-use tauri::{AppHandle, State, Manager};
+use tauri::State;
 use crate::db::AppState;
 
 use crate::models::{
-    Member, CreateMemberArgs, 
-    Cooperative, CreateCoopArgs, 
-    AddMemberToCoopArgs, CoopMember,
-    Due, PayDueArgs, ReceiptInfo
+    Member, CreateMemberArgs, MemberListQuery, MemberListItem, MemberPage, MemberSort, MembershipStatus,
+    Cooperative, CreateCoopArgs, CoopStatus,
+    AddMemberToCoopArgs, AddMembersResult, CoopMember,
+    Due, PayDueArgs, Payment, ReversePaymentArgs, ReceiptInfo,
+    CreditEntry, MemberCredit, RefundCreditArgs,
+    PayMemberBalanceArgs, BalancePayment, PaymentAllocation,
+    PenaltyRule, PenaltyLine,
+    DueKind, DueKindTotal,
+    AuditEntry, AuditLogFilter,
+    LeaveCoopArgs, ExitSettlement, OwnershipLink,
+    DuesPlan, DuesPlanArgs, DuesPlanAmountType, DuesPlanResult,
+    DueChange, DueChangeAction,
+    InvalidTcNumber, InvalidPhone, InvalidDate,
+    Document, AttachDocumentArgs, Backup,
+    MemberStatement, StatementLine
 };
-use sqlx::Row;
-use chrono::Datelike;
+use crate::audit;
+use crate::dates;
+use crate::documents;
+use crate::error::{CommandError, Entity, ErrorCode, FieldError};
+use crate::money::Money;
+use crate::penalties;
+use crate::search;
+use crate::validation;
+use chrono::NaiveDate;
+use sqlx::{QueryBuilder, Row, Sqlite, SqliteConnection, Transaction};
+use std::path::PathBuf;
 
 
 #[tauri::command]
-pub async fn get_payment_receipt_info(state: State<'_, AppState>, coop_member_id: i64) -> Result<ReceiptInfo, String> {
+pub async fn get_payment_receipt_info(state: State<'_, AppState>, coop_member_id: i64) -> Result<ReceiptInfo, CommandError> {
     let info = sqlx::query_as::<_, ReceiptInfo>(
         "SELECT 
             c.name as coop_name,
@@ -26,454 +46,2871 @@ pub async fn get_payment_receipt_info(state: State<'_, AppState>, coop_member_id
     )
     .bind(coop_member_id)
     .fetch_optional(&state.db)
-    .await
-    .map_err(|e| e.to_string())?
-    .ok_or("Coop member info not found")?;
+    .await?
+    .ok_or(CommandError::NotFound(Entity::CoopMember))?;
 
     Ok(info)
 }
 
 
+/// Like every generation command, returns what it changed; with `dry_run`
+/// the changes are only previewed and rolled back.
 #[tauri::command]
-pub async fn generate_yearly_dues(state: State<'_, AppState>, coop_member_id: i64, year: i32, total_amount: f64) -> Result<(), String> {
-    let monthly_amount = total_amount / 12.0;
+pub async fn generate_yearly_dues(
+    state: State<'_, AppState>,
+    coop_member_id: i64,
+    year: i32,
+    total_amount: Money,
+    dry_run: Option<bool>
+) -> Result<Vec<DueChange>, CommandError> {
+    if !total_amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
 
-    for month in 1..=12 {
-        let period = format!("{:04}-{:02}-01", year, month);
+    // Spread the yearly total so the twelve installments add up to it exactly.
+    let monthly_amounts = total_amount.split(12);
 
-        // Check if due exists (standard check for "main" due of that month)
-        // We only update the 'main' due, i.e. not the extras (extra dues usually have same period but we treat them separately? 
-        // Actually, period alone isn't unique if we allow extras. 
-        // But for generation, we assume we are targeting the "primary" slot.
-        // Let's assume the first one found for that period is the "primary".
-        
-        let exists = sqlx::query(
-            "SELECT id, status FROM dues WHERE coop_member_id = ? AND period = ? LIMIT 1"
-        )
-        .bind(coop_member_id)
-        .bind(&period)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
+    let mut tx = state.db.begin().await?;
+    ensure_active_membership(&mut tx, coop_member_id).await?;
 
-        if let Some(due) = exists {
-            let status: String = due.try_get("status").unwrap_or_default();
-            // Only update if unpaid
-            if status != "paid" {
-                 sqlx::query(
-                    "UPDATE dues SET amount = ? WHERE id = ?"
-                )
-                .bind(monthly_amount)
-                .bind(due.try_get::<i64, _>("id").unwrap())
-                .execute(&state.db)
-                .await
-                .map_err(|e| e.to_string())?;
-            }
-        } else {
-             sqlx::query(
-                "INSERT INTO dues (coop_member_id, period, amount, status) VALUES (?, ?, ?, 'unpaid')"
-            )
-            .bind(coop_member_id)
-            .bind(&period)
-            .bind(monthly_amount)
-            .execute(&state.db)
-            .await
-            .map_err(|e| e.to_string())?;
-        }
+    let mut changes = Vec::new();
+    for (month, monthly_amount) in (1..=12).zip(monthly_amounts) {
+        let period = dates::period(year, month).ok_or(CommandError::Invalid(ErrorCode::InvalidDate))?.to_string();
+        changes.push(set_monthly_due(&mut tx, "generate_yearly_dues", coop_member_id, &period, monthly_amount).await?);
     }
 
-    Ok(())
+    apply_member_credit(&mut tx, coop_member_id, &today(), "generate_yearly_dues").await?;
+
+    finish(tx, dry_run, &mut changes).await?;
+    Ok(changes)
 }
 
+/// Moves a due to the recycle bin. A due with payments recorded is only
+/// deleted with `force`; what was paid on it goes to the member's credit.
 #[tauri::command]
-pub async fn delete_due(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    sqlx::query("DELETE FROM dues WHERE id = ?")
-        .bind(id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn delete_due(state: State<'_, AppState>, id: i64, reason: Option<String>, force: Option<bool>) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let coop_member_id = soft_delete_due(&mut tx, "delete_due", id, reason.as_deref(), force.unwrap_or(false)).await?;
+    apply_member_credit(&mut tx, coop_member_id, &today(), "delete_due").await?;
+
+    tx.commit().await?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn delete_yearly_dues(state: State<'_, AppState>, coop_member_id: i64, year: i32) -> Result<(), String> {
+pub async fn delete_yearly_dues(
+    state: State<'_, AppState>,
+    coop_member_id: i64,
+    year: i32,
+    reason: Option<String>,
+    force: Option<bool>
+) -> Result<(), CommandError> {
     let start_date = format!("{:04}-01-01", year);
     let end_date = format!("{:04}-12-31", year);
 
-    sqlx::query("DELETE FROM dues WHERE coop_member_id = ? AND period BETWEEN ? AND ?")
-        .bind(coop_member_id)
-        .bind(start_date)
-        .bind(end_date)
-        .execute(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
+    let mut tx = state.db.begin().await?;
+
+    let due_ids: Vec<i64> = sqlx::query(
+        "SELECT id FROM dues WHERE coop_member_id = ? AND period BETWEEN ? AND ? AND deleted_at IS NULL"
+    )
+    .bind(coop_member_id)
+    .bind(&start_date)
+    .bind(&end_date)
+    .fetch_all(&mut *tx)
+    .await?
+    .iter()
+    .map(|row| row.try_get("id").unwrap_or_default())
+    .collect();
+
+    // One due with payments stops the whole year unless forced.
+    for due_id in due_ids {
+        soft_delete_due(&mut tx, "delete_yearly_dues", due_id, reason.as_deref(), force.unwrap_or(false)).await?;
+    }
+    apply_member_credit(&mut tx, coop_member_id, &today(), "delete_yearly_dues").await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_deleted_dues(state: State<'_, AppState>, coop_member_id: i64) -> Result<Vec<Due>, CommandError> {
+    let dues = sqlx::query_as::<_, Due>(
+        "SELECT id, coop_member_id, period, amount, paid_amount, status, payment_date,
+                COALESCE(due_date, period) AS due_date, penalty_of, kind, description,
+                deleted_at, deleted_reason
+         FROM dues
+         WHERE coop_member_id = ? AND deleted_at IS NOT NULL
+         ORDER BY deleted_at DESC, id DESC"
+    )
+    .bind(coop_member_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(dues)
+}
+
+/// Brings a due back from the recycle bin. It comes back unpaid when its
+/// payments were moved to credit, and the member's credit is applied again.
+#[tauri::command]
+pub async fn restore_due(state: State<'_, AppState>, id: i64) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_due(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Due))?;
+    if before.deleted_at.is_none() {
+        return Err(CommandError::Conflict(ErrorCode::DueNotDeleted));
+    }
+
+    if before.kind == DueKind::Monthly {
+        let taken = sqlx::query(
+            "SELECT id FROM dues WHERE coop_member_id = ? AND period = ? AND kind = 'monthly' AND deleted_at IS NULL"
+        )
+        .bind(before.coop_member_id)
+        .bind(&before.period)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if taken.is_some() {
+            return Err(CommandError::Conflict(ErrorCode::PeriodExists));
+        }
+    }
+
+    sqlx::query("UPDATE dues SET deleted_at = NULL, deleted_reason = NULL WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    let coop_member_id = before.coop_member_id;
+    audit_due(&mut tx, "restore_due", id, Some(before)).await?;
+    apply_member_credit(&mut tx, coop_member_id, &today(), "restore_due").await?;
+
+    tx.commit().await?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn update_due_amount(state: State<'_, AppState>, id: i64, amount: f64) -> Result<(), String> {
+pub async fn update_due_amount(state: State<'_, AppState>, id: i64, amount: Money) -> Result<(), CommandError> {
     // Only allow update if not fully paid? Or allow anyway but might look weird if paid > amount.
-    // For now, simple update.
+    // For now, simple update; the status follows the new amount.
+    if !amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_due(&mut tx, id)
+        .await?
+        .filter(|due| due.deleted_at.is_none())
+        .ok_or(CommandError::NotFound(Entity::Due))?;
+
     sqlx::query("UPDATE dues SET amount = ? WHERE id = ?")
         .bind(amount)
         .bind(id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
+        .execute(&mut *tx)
+        .await?;
+
+    let coop_member_id = settle_due(&mut tx, id, &today(), "update_due_amount").await?;
+    audit_due(&mut tx, "update_due_amount", id, Some(before)).await?;
+    apply_member_credit(&mut tx, coop_member_id, &today(), "update_due_amount").await?;
+
+    tx.commit().await?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn add_extra_due(state: State<'_, AppState>, coop_member_id: i64, year: i32, month: i32, amount: f64) -> Result<(), String> {
-    let period = format!("{:04}-{:02}-01", year, month);
-    sqlx::query(
-        "INSERT INTO dues (coop_member_id, period, amount, status) VALUES (?, ?, ?, 'unpaid')"
+pub async fn add_extra_due(
+    state: State<'_, AppState>,
+    coop_member_id: i64,
+    year: i32,
+    month: i32,
+    amount: Money,
+    kind: Option<DueKind>,
+    description: Option<String>
+) -> Result<(), CommandError> {
+    // Monthly dues come from generation and penalties from calculate_penalties.
+    let kind = kind.unwrap_or(DueKind::Extra);
+    if matches!(kind, DueKind::Monthly | DueKind::Penalty) {
+        return Err(CommandError::Invalid(ErrorCode::InvalidExtraDueKind));
+    }
+    if !amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let period = u32::try_from(month)
+        .ok()
+        .and_then(|month| dates::period(year, month))
+        .ok_or(CommandError::Invalid(ErrorCode::InvalidDate))?
+        .to_string();
+    let mut tx = state.db.begin().await?;
+    ensure_active_membership(&mut tx, coop_member_id).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO dues (coop_member_id, period, amount, status, kind, description) VALUES (?, ?, ?, 'unpaid', ?, ?)"
     )
     .bind(coop_member_id)
     .bind(period)
     .bind(amount)
-    .execute(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .bind(kind)
+    .bind(description)
+    .execute(&mut *tx)
+    .await?;
+
+    audit_due(&mut tx, "add_extra_due", result.last_insert_rowid(), None).await?;
+    apply_member_credit(&mut tx, coop_member_id, &today(), "add_extra_due").await?;
+
+    tx.commit().await?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn generate_dues(state: State<'_, AppState>, coop_member_id: i64, monthly_amount: f64) -> Result<(), String> {
+pub async fn generate_dues(
+    state: State<'_, AppState>,
+    coop_member_id: i64,
+    monthly_amount: Money,
+    dry_run: Option<bool>
+) -> Result<Vec<DueChange>, CommandError> {
+    if !monthly_amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    ensure_active_membership(&mut tx, coop_member_id).await?;
+
     // 1. Get Cooperative Member Entry Date
     let member_entry = sqlx::query(
         "SELECT entry_date FROM cooperative_members WHERE id = ?"
     )
     .bind(coop_member_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| e.to_string())?
-    .ok_or("Member not found in cooperative")?;
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::CoopMember))?;
 
-    // Flexible date parsing: try YYYY-MM-DD, fall back if needed
     let entry_date_str: String = member_entry.try_get("entry_date").unwrap_or_default();
-    
-    let start_date = chrono::NaiveDate::parse_from_str(&entry_date_str, "%Y-%m-%d")
-        .map_err(|_| "Invalid date format")?;
+    let entry_date = dates::parse(&entry_date_str).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
     let now = chrono::Local::now().date_naive();
-    
-    // 2. Iterate months from start_date to now
-    let mut current_date = start_date;
+
+    // 2. Iterate months from the month of entry to now; periods are month starts
+    let mut changes = Vec::new();
+    let mut current_date = dates::month_start(entry_date);
     while current_date <= now {
-        let period = current_date.format("%Y-%m-%d").to_string(); // Use full date as period reference
+        let period = current_date.to_string();
         
         // 3. Check if due already exists
         let exists = sqlx::query(
-            "SELECT id FROM dues WHERE coop_member_id = ? AND period = ?"
+            "SELECT id, amount FROM dues WHERE coop_member_id = ? AND period = ? AND kind = 'monthly' AND deleted_at IS NULL"
         )
         .bind(coop_member_id)
         .bind(&period)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
+        .fetch_optional(&mut *tx)
+        .await?;
 
-        if exists.is_none() {
-            sqlx::query(
+        if let Some(due) = exists {
+            let amount: Money = due.try_get("amount")?;
+            changes.push(DueChange {
+                coop_member_id,
+                period,
+                action: DueChangeAction::Skip,
+                due_id: Some(due.try_get("id")?),
+                before_amount: Some(amount),
+                after_amount: Some(amount),
+                reason: Some("exists".to_string()),
+            });
+        } else {
+            let result = sqlx::query(
                 "INSERT INTO dues (coop_member_id, period, amount, status) VALUES (?, ?, ?, 'unpaid')"
             )
             .bind(coop_member_id)
             .bind(&period)
             .bind(monthly_amount)
-            .execute(&state.db)
-            .await
-            .map_err(|e| e.to_string())?;
-        }
+            .execute(&mut *tx)
+            .await?;
 
-        // Advance 1 month
-        if current_date.month() == 12 {
-            current_date = current_date.with_year(current_date.year() + 1).unwrap().with_month(1).unwrap();
-        } else {
-            current_date = current_date.with_month(current_date.month() + 1).unwrap();
+            audit_due(&mut tx, "generate_dues", result.last_insert_rowid(), None).await?;
+            changes.push(DueChange {
+                coop_member_id,
+                period,
+                action: DueChangeAction::Insert,
+                due_id: Some(result.last_insert_rowid()),
+                before_amount: None,
+                after_amount: Some(monthly_amount),
+                reason: None,
+            });
         }
+
+        current_date = dates::next_month_start(current_date);
     }
 
-    Ok(())
+    apply_member_credit(&mut tx, coop_member_id, &today(), "generate_dues").await?;
+
+    finish(tx, dry_run, &mut changes).await?;
+    Ok(changes)
 }
 
 #[tauri::command]
-pub async fn add_next_due(state: State<'_, AppState>, coop_member_id: i64, monthly_amount: f64) -> Result<(), String> {
+pub async fn add_next_due(
+    state: State<'_, AppState>,
+    coop_member_id: i64,
+    monthly_amount: Money,
+    dry_run: Option<bool>
+) -> Result<DueChange, CommandError> {
+    if !monthly_amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let mut tx = state.db.begin().await?;
+    ensure_active_membership(&mut tx, coop_member_id).await?;
+
     // 1. Find the latest due period
     let last_due = sqlx::query(
-        "SELECT period FROM dues WHERE coop_member_id = ? ORDER BY period DESC LIMIT 1"
+        "SELECT period FROM dues WHERE coop_member_id = ? AND kind = 'monthly' AND deleted_at IS NULL ORDER BY period DESC LIMIT 1"
     )
     .bind(coop_member_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .fetch_optional(&mut *tx)
+    .await?;
 
     let next_date = if let Some(due) = last_due {
         // If dues exist, add 1 month to the last one
         let period_str: String = due.try_get("period").unwrap_or_default();
-        let last_date = chrono::NaiveDate::parse_from_str(&period_str, "%Y-%m-%d")
-            .map_err(|_| "Invalid date format in DB")?;
-        
-        if last_date.month() == 12 {
-            last_date.with_year(last_date.year() + 1).unwrap().with_month(1).unwrap()
-        } else {
-            last_date.with_month(last_date.month() + 1).unwrap()
-        }
+        let last_date = dates::parse(&period_str).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
+        dates::next_month_start(last_date)
     } else {
-        // If no dues exist, use the member's entry date
+        // If no dues exist, start with the month of the member's entry
         let member_entry = sqlx::query(
             "SELECT entry_date FROM cooperative_members WHERE id = ?"
         )
         .bind(coop_member_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| e.to_string())?
-        .ok_or("Member not found")?;
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::Member))?;
 
         let entry_date_str: String = member_entry.try_get("entry_date").unwrap_or_default();
-        chrono::NaiveDate::parse_from_str(&entry_date_str, "%Y-%m-%d")
-            .map_err(|_| "Invalid entry date format")?
+        let entry_date = dates::parse(&entry_date_str).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
+        dates::month_start(entry_date)
     };
 
-    let period = next_date.format("%Y-%m-%d").to_string();
+    let period = next_date.to_string();
 
     // Check if distinct (though logic implies it should be new, double check to avoid dupes if race condition)
     let exists = sqlx::query(
-        "SELECT id FROM dues WHERE coop_member_id = ? AND period = ?"
+        "SELECT id FROM dues WHERE coop_member_id = ? AND period = ? AND kind = 'monthly' AND deleted_at IS NULL"
     )
     .bind(coop_member_id)
     .bind(&period)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .fetch_optional(&mut *tx)
+    .await?;
 
-    if exists.is_none() {
-        sqlx::query(
-            "INSERT INTO dues (coop_member_id, period, amount, status) VALUES (?, ?, ?, 'unpaid')"
-        )
-        .bind(coop_member_id)
-        .bind(period)
-        .bind(monthly_amount)
-        .execute(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
-    } else {
-        return Err("Bu dönem için aidat zaten mevcut.".to_string());
+    if exists.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::PeriodExists));
     }
 
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn get_member_dues(state: State<'_, AppState>, coop_member_id: i64) -> Result<Vec<Due>, String> {
-    let dues = sqlx::query_as::<_, Due>(
-        "SELECT id, coop_member_id, period, amount, paid_amount, status, payment_date 
-         FROM dues 
-         WHERE coop_member_id = ? 
-         ORDER BY period ASC"
+    let result = sqlx::query(
+        "INSERT INTO dues (coop_member_id, period, amount, status) VALUES (?, ?, ?, 'unpaid')"
     )
     .bind(coop_member_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .bind(&period)
+    .bind(monthly_amount)
+    .execute(&mut *tx)
+    .await?;
 
-    Ok(dues)
+    audit_due(&mut tx, "add_next_due", result.last_insert_rowid(), None).await?;
+
+    apply_member_credit(&mut tx, coop_member_id, &today(), "add_next_due").await?;
+
+    let mut change = DueChange {
+        coop_member_id,
+        period,
+        action: DueChangeAction::Insert,
+        due_id: Some(result.last_insert_rowid()),
+        before_amount: None,
+        after_amount: Some(monthly_amount),
+        reason: None,
+    };
+    finish(tx, dry_run, std::slice::from_mut(&mut change)).await?;
+    Ok(change)
 }
 
+/// Creates the cooperative's dues plan for a year, or replaces it.
 #[tauri::command]
-pub async fn pay_due(state: State<'_, AppState>, args: PayDueArgs) -> Result<(), String> {
-    // 1. Get Due info
-    let due = sqlx::query_as::<_, Due>(
-        "SELECT id, coop_member_id, period, amount, paid_amount, status, payment_date FROM dues WHERE id = ?"
+pub async fn save_dues_plan(state: State<'_, AppState>, plan: DuesPlanArgs) -> Result<i64, CommandError> {
+    let start_month = plan.start_month.unwrap_or(1);
+    if !(1..=12).contains(&start_month) {
+        return Err(CommandError::Invalid(ErrorCode::StartMonthOutOfRange));
+    }
+    if !plan.amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let before = sqlx::query_as::<_, DuesPlan>(
+        "SELECT id, coop_id, year, amount, amount_type, start_month, created_at FROM dues_plans WHERE coop_id = ? AND year = ?"
     )
-    .bind(args.due_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| e.to_string())?
-    .ok_or("Due not found")?;
+    .bind(plan.coop_id)
+    .bind(plan.year)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let id: i64 = sqlx::query(
+        "INSERT INTO dues_plans (coop_id, year, amount, amount_type, start_month) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(coop_id, year) DO UPDATE SET
+            amount = excluded.amount,
+            amount_type = excluded.amount_type,
+            start_month = excluded.start_month
+         RETURNING id"
+    )
+    .bind(plan.coop_id)
+    .bind(plan.year)
+    .bind(plan.amount)
+    .bind(plan.amount_type)
+    .bind(start_month)
+    .fetch_one(&mut *tx)
+    .await?
+    .try_get("id")?;
 
-    let new_paid = due.paid_amount + args.amount;
-    let new_status = if new_paid >= due.amount { "paid" } else { "partial" };
+    let after = fetch_dues_plan(&mut tx, id).await?;
+    audit::record(&mut tx, "save_dues_plan", "dues_plan", id, before.as_ref().map(audit::snapshot), after.as_ref().map(audit::snapshot)).await?;
 
-    sqlx::query(
-        "UPDATE dues SET paid_amount = ?, status = ?, payment_date = ? WHERE id = ?"
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn get_dues_plans(state: State<'_, AppState>, coop_id: i64) -> Result<Vec<DuesPlan>, CommandError> {
+    let plans = sqlx::query_as::<_, DuesPlan>(
+        "SELECT id, coop_id, year, amount, amount_type, start_month, created_at FROM dues_plans
+         WHERE coop_id = ?
+         ORDER BY year DESC"
     )
-    .bind(new_paid)
-    .bind(new_status)
-    .bind(args.payment_date)
-    .bind(args.due_id)
-    .execute(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .bind(coop_id)
+    .fetch_all(&state.db)
+    .await?;
 
-    Ok(())
+    Ok(plans)
 }
 
+/// Removes a plan; dues already generated from it stay.
 #[tauri::command]
-pub async fn add_members_to_coop(
-    state: State<'_, AppState>,
-    args: AddMemberToCoopArgs
-) -> Result<(), String> {
-    // Start a transaction
-    let mut tx = state.db.begin().await.map_err(|e| e.to_string())?;
+pub async fn delete_dues_plan(state: State<'_, AppState>, id: i64) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
 
-    for member_id in args.member_ids {
-        sqlx::query(
-            "INSERT INTO cooperative_members (coop_id, member_id, entry_date) VALUES (?, ?, ?)"
-        )
-        .bind(args.coop_id)
-        .bind(member_id)
-        .bind(&args.entry_date)
+    let before = fetch_dues_plan(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::DuesPlan))?;
+
+    sqlx::query("DELETE FROM dues_plans WHERE id = ?")
+        .bind(id)
         .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-    }
+        .await?;
+
+    audit::record(&mut tx, "delete_dues_plan", "dues_plan", id, Some(audit::snapshot(&before)), None).await?;
 
-    tx.commit().await.map_err(|e| e.to_string())?;
+    tx.commit().await?;
     Ok(())
 }
 
+/// Generates the plan's monthly dues for every active member of the
+/// cooperative in one go. Months before a member's entry are left out, paid
+/// dues are kept and unpaid ones take the plan's amount.
 #[tauri::command]
-pub async fn get_coop_members(state: State<'_, AppState>, coop_id: i64) -> Result<Vec<CoopMember>, String> {
-    let members = sqlx::query_as::<_, CoopMember>(
-        "SELECT 
-            cm.id, cm.member_id, m.full_name, m.tc_number, m.phone_1, cm.entry_date
-         FROM cooperative_members cm
-         JOIN members m ON cm.member_id = m.id
-         WHERE cm.coop_id = ?
-         ORDER BY m.full_name ASC"
-    )
-    .bind(coop_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+pub async fn apply_dues_plan(state: State<'_, AppState>, plan_id: i64, dry_run: Option<bool>) -> Result<DuesPlanResult, CommandError> {
+    let mut tx = state.db.begin().await?;
 
-    Ok(members)
-}
+    let plan = fetch_dues_plan(&mut tx, plan_id).await?.ok_or(CommandError::NotFound(Entity::DuesPlan))?;
 
-#[tauri::command]
-pub async fn get_available_members(state: State<'_, AppState>, coop_id: i64) -> Result<Vec<Member>, String> {
-    let members = sqlx::query_as::<_, Member>(
-        "SELECT * FROM members 
-         WHERE id NOT IN (SELECT member_id FROM cooperative_members WHERE coop_id = ?)
-         ORDER BY full_name ASC"
+    // A yearly amount is spread over the months the plan covers.
+    let months: Vec<u32> = (plan.start_month..=12).collect();
+    let amounts = match plan.amount_type {
+        DuesPlanAmountType::Monthly => vec![plan.amount; months.len()],
+        DuesPlanAmountType::Yearly => plan.amount.split(months.len() as u32),
+    };
+
+    let members = sqlx::query(
+        "SELECT id, entry_date FROM cooperative_members WHERE coop_id = ? AND exit_date IS NULL ORDER BY id ASC"
     )
-    .bind(coop_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .bind(plan.coop_id)
+    .fetch_all(&mut *tx)
+    .await?;
 
-    Ok(members)
+    let mut result = DuesPlanResult {
+        plan_id,
+        member_count: members.len() as i64,
+        inserted: 0,
+        updated: 0,
+        skipped: 0,
+        changes: Vec::new(),
+    };
+
+    for member in members {
+        let coop_member_id: i64 = member.try_get("id")?;
+        let entry_date: String = member.try_get("entry_date")?;
+        let entry_period = dates::parse(&entry_date)
+            .map(dates::month_start)
+            .ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
+
+        for (&month, &amount) in months.iter().zip(&amounts) {
+            let period = dates::period(plan.year, month).ok_or(CommandError::Invalid(ErrorCode::InvalidDate))?;
+            let change = if period < entry_period {
+                DueChange {
+                    coop_member_id,
+                    period: period.to_string(),
+                    action: DueChangeAction::Skip,
+                    due_id: None,
+                    before_amount: None,
+                    after_amount: None,
+                    reason: Some("before_entry".to_string()),
+                }
+            } else {
+                set_monthly_due(&mut tx, "apply_dues_plan", coop_member_id, &period.to_string(), amount).await?
+            };
+            match change.action {
+                DueChangeAction::Insert => result.inserted += 1,
+                DueChangeAction::Update => result.updated += 1,
+                DueChangeAction::Skip => result.skipped += 1,
+            }
+            result.changes.push(change);
+        }
+
+        apply_member_credit(&mut tx, coop_member_id, &today(), "apply_dues_plan").await?;
+    }
+
+    finish(tx, dry_run, &mut result.changes).await?;
+    Ok(result)
 }
 
-#[tauri::command]
-pub async fn get_coop_details(state: State<'_, AppState>, id: i64) -> Result<Cooperative, String> {
-    let coop = sqlx::query_as::<_, Cooperative>(
-        "SELECT id, name, start_date, created_at FROM cooperatives WHERE id = ?"
+async fn fetch_dues_plan(conn: &mut SqliteConnection, id: i64) -> Result<Option<DuesPlan>, CommandError> {
+    sqlx::query_as::<_, DuesPlan>(
+        "SELECT id, coop_id, year, amount, amount_type, start_month, created_at FROM dues_plans WHERE id = ?"
     )
     .bind(id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *conn)
     .await
-    .map_err(|e| e.to_string())?
-    .ok_or("Cooperative not found")?;
+    .map_err(CommandError::from)
+}
 
-    Ok(coop)
+/// Commits the generation, or rolls it back when it was only a preview. The
+/// dues a preview inserted are gone afterwards, so their ids are dropped.
+async fn finish(tx: Transaction<'_, Sqlite>, dry_run: Option<bool>, changes: &mut [DueChange]) -> Result<(), CommandError> {
+    if dry_run.unwrap_or(false) {
+        for change in changes.iter_mut().filter(|c| c.action == DueChangeAction::Insert) {
+            change.due_id = None;
+        }
+        tx.rollback().await.map_err(CommandError::from)
+    } else {
+        tx.commit().await.map_err(CommandError::from)
+    }
 }
 
-#[tauri::command]
-pub async fn create_coop(
-    state: State<'_, AppState>,
-    coop: CreateCoopArgs
-) -> Result<i64, String> {
-    let result = sqlx::query(
-        "INSERT INTO cooperatives (name, start_date) VALUES (?, ?)"
+/// Creates the monthly due of a period or, while it is not paid, sets its
+/// amount. Extras, penalties etc. of the period are left alone.
+async fn set_monthly_due(
+    conn: &mut SqliteConnection,
+    command: &str,
+    coop_member_id: i64,
+    period: &str,
+    amount: Money,
+) -> Result<DueChange, CommandError> {
+    let exists = sqlx::query(
+        "SELECT id, status, amount FROM dues WHERE coop_member_id = ? AND period = ? AND kind = 'monthly' AND deleted_at IS NULL"
     )
-    .bind(coop.name)
-    .bind(coop.start_date)
-    .execute(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .bind(coop_member_id)
+    .bind(period)
+    .fetch_optional(&mut *conn)
+    .await?;
 
-    Ok(result.last_insert_rowid())
+    let Some(due) = exists else {
+        let result = sqlx::query(
+            "INSERT INTO dues (coop_member_id, period, amount, status) VALUES (?, ?, ?, 'unpaid')"
+        )
+        .bind(coop_member_id)
+        .bind(period)
+        .bind(amount)
+        .execute(&mut *conn)
+        .await?;
+
+        audit_due(conn, command, result.last_insert_rowid(), None).await?;
+        return Ok(DueChange {
+            coop_member_id,
+            period: period.to_string(),
+            action: DueChangeAction::Insert,
+            due_id: Some(result.last_insert_rowid()),
+            before_amount: None,
+            after_amount: Some(amount),
+            reason: None,
+        });
+    };
+
+    let due_id: i64 = due.try_get("id")?;
+    let status: String = due.try_get("status")?;
+    let current: Money = due.try_get("amount")?;
+    let skip = match status.as_str() {
+        "paid" => Some("paid"),
+        _ if current == amount => Some("unchanged"),
+        _ => None,
+    };
+    if let Some(reason) = skip {
+        return Ok(DueChange {
+            coop_member_id,
+            period: period.to_string(),
+            action: DueChangeAction::Skip,
+            due_id: Some(due_id),
+            before_amount: Some(current),
+            after_amount: Some(current),
+            reason: Some(reason.to_string()),
+        });
+    }
+
+    let before = fetch_due(conn, due_id).await?;
+    sqlx::query("UPDATE dues SET amount = ? WHERE id = ?")
+        .bind(amount)
+        .bind(due_id)
+        .execute(&mut *conn)
+        .await?;
+
+    settle_due(conn, due_id, &today(), command).await?;
+    audit_due(conn, command, due_id, before).await?;
+    Ok(DueChange {
+        coop_member_id,
+        period: period.to_string(),
+        action: DueChangeAction::Update,
+        due_id: Some(due_id),
+        before_amount: Some(current),
+        after_amount: Some(amount),
+        reason: None,
+    })
 }
 
 #[tauri::command]
-pub async fn get_coops(state: State<'_, AppState>) -> Result<Vec<Cooperative>, String> {
-    let coops = sqlx::query_as::<_, Cooperative>(
-        "SELECT id, name, start_date, created_at FROM cooperatives ORDER BY start_date DESC"
+pub async fn get_member_dues(state: State<'_, AppState>, coop_member_id: i64) -> Result<Vec<Due>, CommandError> {
+    let dues = sqlx::query_as::<_, Due>(
+        "SELECT id, coop_member_id, period, amount, paid_amount, status, payment_date,
+                COALESCE(due_date, period) AS due_date, penalty_of, kind, description,
+                deleted_at, deleted_reason
+         FROM dues 
+         WHERE coop_member_id = ? AND deleted_at IS NULL
+         ORDER BY period ASC"
     )
+    .bind(coop_member_id)
     .fetch_all(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .await?;
 
-    Ok(coops)
+    Ok(dues)
 }
 
+/// Account statement (hesap ekstresi) of a membership: dues as they fall due
+/// and refunds as debits, money received as credits, in date order with a
+/// running balance. `to` defaults to today; without `from` the statement
+/// starts at the first entry. Settlements from the member's credit only move
+/// money within the account and are left out.
 #[tauri::command]
-pub async fn create_member(
+pub async fn get_member_statement(
     state: State<'_, AppState>,
-    member: CreateMemberArgs
-) -> Result<i64, String> {
-    let result = sqlx::query(
-        "INSERT INTO members (tc_number, full_name, phone_1, phone_2, registration_date) VALUES (?, ?, ?, ?, ?)"
-    )
-    .bind(member.tc_number)
-    .bind(member.full_name)
-    .bind(member.phone_1)
-    .bind(member.phone_2)
-    .bind(member.registration_date)
-    .execute(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    coop_member_id: i64,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>
+) -> Result<MemberStatement, CommandError> {
+    let to = to.unwrap_or_else(|| chrono::Local::now().date_naive());
+    if from.is_some_and(|from| from > to) {
+        return Err(CommandError::Invalid(ErrorCode::InvalidDateRange));
+    }
 
-    Ok(result.last_insert_rowid())
+    let mut conn = state.db.acquire().await?;
+    member_statement(&mut conn, coop_member_id, from, to).await
 }
 
-#[tauri::command]
-pub async fn get_members(state: State<'_, AppState>) -> Result<Vec<Member>, String> {
-    let members = sqlx::query_as::<_, Member>(
-        "SELECT id, tc_number, full_name, phone_1, phone_2, registration_date, created_at FROM members ORDER BY full_name ASC"
+async fn member_statement(
+    conn: &mut SqliteConnection,
+    coop_member_id: i64,
+    from: Option<NaiveDate>,
+    to: NaiveDate,
+) -> Result<MemberStatement, CommandError> {
+    let to_text = to.to_string();
+    let header = sqlx::query(
+        "SELECT c.name AS coop_name, m.full_name, m.tc_number
+         FROM cooperative_members cm
+         JOIN cooperatives c ON c.id = cm.coop_id
+         JOIN members m ON m.id = cm.member_id
+         WHERE cm.id = ?"
     )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .bind(coop_member_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::CoopMember))?;
 
-    Ok(members)
-}
+    // (date, order on that date, line)
+    let mut entries: Vec<(NaiveDate, i64, StatementLine)> = Vec::new();
+    let stored_date = |value: String| dates::parse(&value).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate));
+    let line = |kind: &str, description: String, amount: Money| StatementLine {
+        date: String::new(),
+        kind: kind.to_string(),
+        description,
+        debit: if amount.is_positive() { amount } else { Money::ZERO },
+        credit: if amount.is_negative() { -amount } else { Money::ZERO },
+        balance: Money::ZERO,
+        due_id: None,
+        payment_id: None,
+        credit_entry_id: None,
+        receipt_no: None,
+    };
+
+    let dues = sqlx::query(
+        "SELECT id, period, COALESCE(due_date, period) AS due_date, amount, kind, description
+         FROM dues
+         WHERE coop_member_id = ? AND deleted_at IS NULL AND COALESCE(due_date, period) <= ?"
+    )
+    .bind(coop_member_id)
+    .bind(&to_text)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for due in dues {
+        let id: i64 = due.try_get("id")?;
+        let period: String = due.try_get("period")?;
+        let kind: DueKind = due.try_get("kind")?;
+        let description: Option<String> = due.try_get("description")?;
+        let month = dates::month_label(stored_date(period)?);
+        let description = match (kind, description) {
+            (DueKind::Monthly, _) => format!("{} aidatı", month),
+            (DueKind::Penalty, _) => format!("{} gecikme zammı", month),
+            (_, Some(description)) => description,
+            (DueKind::Extra, None) => format!("{} ek ödeme", month),
+            (DueKind::ShareCapital, None) => format!("{} sermaye payı", month),
+            (DueKind::Other, None) => format!("{} diğer borç", month),
+        };
+        let mut entry = line("due", description, due.try_get("amount")?);
+        entry.due_id = Some(id);
+        entries.push((stored_date(due.try_get("due_date")?)?, 0, entry));
+    }
+
+    // Payments of deleted dues still count: the money was received.
+    let payments = sqlx::query(
+        "SELECT p.id, p.due_id, p.amount, p.payment_date, p.receipt_no, p.reason, d.period
+         FROM payments p
+         JOIN dues d ON d.id = p.due_id
+         WHERE d.coop_member_id = ? AND p.method != ? AND p.payment_date <= ?"
+    )
+    .bind(coop_member_id)
+    .bind(CREDIT_METHOD)
+    .bind(&to_text)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for payment in payments {
+        let amount: Money = payment.try_get("amount")?;
+        let period: String = payment.try_get("period")?;
+        let month = dates::month_label(stored_date(period)?);
+        let mut entry = if amount.is_negative() {
+            let reason: Option<String> = payment.try_get("reason")?;
+            let description = match reason {
+                Some(reason) => format!("{} ödeme iptali: {}", month, reason),
+                None => format!("{} ödeme iptali", month),
+            };
+            line("reversal", description, -amount)
+        } else {
+            line("payment", format!("{} ödemesi", month), -amount)
+        };
+        entry.due_id = Some(payment.try_get("due_id")?);
+        entry.payment_id = Some(payment.try_get("id")?);
+        entry.receipt_no = payment.try_get("receipt_no")?;
+        entries.push((stored_date(payment.try_get("payment_date")?)?, 1, entry));
+    }
+
+    // Only entries that move money into or out of the account; applied,
+    // overpayment and reversal entries mirror a credit settlement above.
+    let credits = sqlx::query(
+        "SELECT id, amount, entry_type, entry_date, note, receipt_no
+         FROM member_credits
+         WHERE coop_member_id = ? AND entry_type IN ('deposit', 'refund', 'transfer') AND entry_date <= ?"
+    )
+    .bind(coop_member_id)
+    .bind(&to_text)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for credit in credits {
+        let amount: Money = credit.try_get("amount")?;
+        let entry_type: String = credit.try_get("entry_type")?;
+        let note: Option<String> = credit.try_get("note")?;
+        let description = match entry_type.as_str() {
+            "deposit" => "Hesaba yatırılan ödeme".to_string(),
+            "refund" => note.map_or("Alacak iadesi".to_string(), |note| format!("Alacak iadesi: {}", note)),
+            _ => note.unwrap_or_else(|| "Üyelik devri".to_string()),
+        };
+        let mut entry = line(&entry_type, description, -amount);
+        entry.credit_entry_id = Some(credit.try_get("id")?);
+        entry.receipt_no = credit.try_get("receipt_no")?;
+        entries.push((stored_date(credit.try_get("entry_date")?)?, 1, entry));
+    }
+
+    entries.sort_by_key(|(date, order, _)| (*date, *order));
+
+    let mut opening_balance = Money::ZERO;
+    let mut balance = Money::ZERO;
+    let mut lines = Vec::new();
+    for (date, _, mut entry) in entries {
+        balance += entry.debit - entry.credit;
+        if from.is_some_and(|from| date < from) {
+            opening_balance = balance;
+            continue;
+        }
+        entry.date = date.to_string();
+        entry.balance = balance;
+        lines.push(entry);
+    }
+
+    Ok(MemberStatement {
+        coop_member_id,
+        coop_name: header.try_get("coop_name")?,
+        member_full_name: header.try_get("full_name")?,
+        member_tc: header.try_get("tc_number")?,
+        from: from.map(|from| from.to_string()),
+        to: to_text,
+        opening_balance,
+        total_debit: lines.iter().map(|l| l.debit).sum(),
+        total_credit: lines.iter().map(|l| l.credit).sum(),
+        closing_balance: balance,
+        lines,
+    })
+}
+
+/// Due totals broken down by kind, for a whole cooperative or, with
+/// `coop_member_id`, for a single membership.
+#[tauri::command]
+pub async fn get_due_totals_by_kind(state: State<'_, AppState>, coop_id: i64, coop_member_id: Option<i64>) -> Result<Vec<DueKindTotal>, CommandError> {
+    let totals = sqlx::query_as::<_, DueKindTotal>(
+        "SELECT d.kind, COUNT(*) AS count,
+                SUM(d.amount) AS amount,
+                SUM(d.paid_amount) AS paid_amount,
+                SUM(d.amount - d.paid_amount) AS outstanding
+         FROM dues d
+         JOIN cooperative_members cm ON cm.id = d.coop_member_id
+         WHERE cm.coop_id = ? AND (? IS NULL OR d.coop_member_id = ?) AND d.deleted_at IS NULL
+         GROUP BY d.kind
+         ORDER BY d.kind ASC"
+    )
+    .bind(coop_id)
+    .bind(coop_member_id)
+    .bind(coop_member_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(totals)
+}
+
+#[tauri::command]
+pub async fn pay_due(state: State<'_, AppState>, args: PayDueArgs) -> Result<i64, CommandError> {
+    if !args.amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let payment_date = args.payment_date.to_string();
+    let mut tx = state.db.begin().await?;
+
+    // 1. Make sure the due exists
+    sqlx::query("SELECT id FROM dues WHERE id = ? AND deleted_at IS NULL")
+        .bind(args.due_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::Due))?;
+
+    // 2. Record the collection in the ledger, then derive the due's totals from it
+    let payment_id = insert_payment(&mut tx, "pay_due", args.due_id, args.amount, &payment_date, args.method.as_deref(), args.receipt_no.as_deref(), args.created_by.as_deref()).await?;
+
+    // 3. Anything paid beyond the due goes to the member's credit and on to the next dues
+    let coop_member_id = settle_due(&mut tx, args.due_id, &payment_date, "pay_due").await?;
+    apply_member_credit(&mut tx, coop_member_id, &payment_date, "pay_due").await?;
+
+    tx.commit().await?;
+    Ok(payment_id)
+}
+
+#[tauri::command]
+pub async fn pay_member_balance(state: State<'_, AppState>, args: PayMemberBalanceArgs) -> Result<BalancePayment, CommandError> {
+    if !args.amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let payment_date = args.payment_date.to_string();
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query("SELECT id FROM cooperative_members WHERE id = ?")
+        .bind(args.coop_member_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::CoopMember))?;
+
+    // Same order as get_member_dues, so the oldest debt is settled first.
+    let open_dues = sqlx::query(
+        "SELECT id, period, amount, paid_amount FROM dues
+         WHERE coop_member_id = ? AND status != 'paid' AND deleted_at IS NULL
+         ORDER BY period ASC, id ASC"
+    )
+    .bind(args.coop_member_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut remaining = args.amount;
+    let mut allocations = Vec::new();
+
+    for due in open_dues {
+        if !remaining.is_positive() {
+            break;
+        }
+        let due_id: i64 = due.try_get("id")?;
+        let period: String = due.try_get("period")?;
+        let amount: Money = due.try_get("amount")?;
+        let paid_amount: Money = due.try_get("paid_amount")?;
+
+        let allocated = (amount - paid_amount).min(remaining);
+        if !allocated.is_positive() {
+            continue;
+        }
+
+        let payment_id = insert_payment(&mut tx, "pay_member_balance", due_id, allocated, &payment_date, args.method.as_deref(), args.receipt_no.as_deref(), args.created_by.as_deref()).await?;
+        refresh_due_totals(&mut tx, due_id, "pay_member_balance").await?;
+
+        remaining -= allocated;
+        allocations.push(PaymentAllocation {
+            payment_id,
+            due_id,
+            period,
+            amount: allocated,
+            remaining_due: amount - paid_amount - allocated,
+        });
+    }
+
+    // Whatever is left once every due is settled is kept as the member's credit.
+    if remaining.is_positive() {
+        insert_credit_entry(&mut tx, "pay_member_balance", args.coop_member_id, remaining, "deposit", &payment_date, None, None, args.receipt_no.as_deref(), args.created_by.as_deref()).await?;
+    }
+
+    tx.commit().await?;
+    Ok(BalancePayment {
+        coop_member_id: args.coop_member_id,
+        payment_date,
+        receipt_no: args.receipt_no,
+        total: args.amount,
+        allocations,
+        credited: remaining,
+    })
+}
+
+#[tauri::command]
+pub async fn get_due_payments(state: State<'_, AppState>, due_id: i64) -> Result<Vec<Payment>, CommandError> {
+    let payments = sqlx::query_as::<_, Payment>(
+        "SELECT p.id, p.due_id, p.amount, p.payment_date, p.method, p.receipt_no, p.created_by, p.created_at,
+                p.reversal_of, r.id AS reversed_by, p.reason
+         FROM payments p
+         LEFT JOIN payments r ON r.reversal_of = p.id
+         WHERE p.due_id = ?
+         ORDER BY p.payment_date ASC, p.id ASC"
+    )
+    .bind(due_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(payments)
+}
+
+#[tauri::command]
+pub async fn reverse_payment(state: State<'_, AppState>, args: ReversePaymentArgs) -> Result<i64, CommandError> {
+    let reason = args.reason.trim();
+    if reason.is_empty() {
+        return Err(CommandError::Invalid(ErrorCode::ReasonRequired));
+    }
+
+    let reversal_date = args.reversal_date.to_string();
+    let mut tx = state.db.begin().await?;
+
+    let original = sqlx::query(
+        "SELECT p.due_id, p.amount, p.method, p.reversal_of, r.id AS reversed_by, d.deleted_at
+         FROM payments p
+         JOIN dues d ON d.id = p.due_id
+         LEFT JOIN payments r ON r.reversal_of = p.id
+         WHERE p.id = ?"
+    )
+    .bind(args.payment_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::Payment))?;
+
+    let reversal_of: Option<i64> = original.try_get("reversal_of")?;
+    let reversed_by: Option<i64> = original.try_get("reversed_by")?;
+    if reversal_of.is_some() {
+        return Err(CommandError::Invalid(ErrorCode::ReversalOfReversal));
+    }
+    if reversed_by.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::AlreadyReversed));
+    }
+    let deleted_at: Option<String> = original.try_get("deleted_at")?;
+    if deleted_at.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::PaymentOfDeletedDue));
+    }
+
+    let due_id: i64 = original.try_get("due_id")?;
+    let amount: Money = original.try_get("amount")?;
+    let method: String = original.try_get("method")?;
+    if !amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::NotACollection));
+    }
+
+    let before = fetch_payment(&mut tx, args.payment_id).await?;
+
+    // The original entry stays untouched; the voiding entry cancels it out.
+    let result = sqlx::query(
+        "INSERT INTO payments (due_id, amount, payment_date, method, created_by, reversal_of, reason)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(due_id)
+    .bind(-amount)
+    .bind(&reversal_date)
+    .bind(&method)
+    .bind(&args.created_by)
+    .bind(args.payment_id)
+    .bind(reason)
+    .execute(&mut *tx)
+    .await?;
+
+    let after = fetch_payment(&mut tx, args.payment_id).await?;
+    audit::record(&mut tx, "reverse_payment", "payment", args.payment_id, before.as_ref().map(audit::snapshot), after.as_ref().map(audit::snapshot)).await?;
+
+    // Undoing a settlement from credit gives the amount back to the member's credit.
+    if method == CREDIT_METHOD {
+        let coop_member_id: i64 = sqlx::query("SELECT coop_member_id FROM dues WHERE id = ?")
+            .bind(due_id)
+            .fetch_one(&mut *tx)
+            .await?
+            .try_get("coop_member_id")?;
+
+        insert_credit_entry(&mut tx, "reverse_payment", coop_member_id, amount, "reversal", &reversal_date, Some(result.last_insert_rowid()), Some(reason), None, args.created_by.as_deref()).await?;
+    }
+
+    // Credit is not re-applied here, otherwise it would immediately settle the due again.
+    settle_due(&mut tx, due_id, &reversal_date, "reverse_payment").await?;
+
+    tx.commit().await?;
+    Ok(result.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn get_member_credit(state: State<'_, AppState>, coop_member_id: i64) -> Result<MemberCredit, CommandError> {
+    let entries = sqlx::query_as::<_, CreditEntry>(
+        "SELECT c.id, c.coop_member_id, c.amount, c.entry_type, c.entry_date, c.payment_id,
+                p.due_id, c.note, c.receipt_no, c.created_by, c.created_at
+         FROM member_credits c
+         LEFT JOIN payments p ON p.id = c.payment_id
+         WHERE c.coop_member_id = ?
+         ORDER BY c.entry_date ASC, c.id ASC"
+    )
+    .bind(coop_member_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let balance = entries.iter().map(|e| e.amount).sum();
+
+    Ok(MemberCredit { coop_member_id, balance, entries })
+}
+
+#[tauri::command]
+pub async fn refund_member_credit(state: State<'_, AppState>, args: RefundCreditArgs) -> Result<i64, CommandError> {
+    if !args.amount.is_positive() {
+        return Err(CommandError::Invalid(ErrorCode::AmountNotPositive));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let balance = credit_balance(&mut tx, args.coop_member_id).await?;
+    if args.amount > balance {
+        return Err(CommandError::Conflict(ErrorCode::RefundExceedsCredit { balance }));
+    }
+
+    let id = insert_credit_entry(&mut tx, "refund_member_credit", args.coop_member_id, -args.amount, "refund", &args.refund_date.to_string(), None, args.note.as_deref(), None, args.created_by.as_deref()).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn get_penalty_rule(state: State<'_, AppState>, coop_id: i64) -> Result<Option<PenaltyRule>, CommandError> {
+    let mut conn = state.db.acquire().await?;
+    fetch_penalty_rule(&mut conn, coop_id).await
+}
+
+#[tauri::command]
+pub async fn set_penalty_rule(state: State<'_, AppState>, rule: PenaltyRule) -> Result<(), CommandError> {
+    let cap_valid = match rule.cap_rate {
+        Some(cap) => cap.is_finite() && cap > 0.0,
+        None => true,
+    };
+    if !rule.monthly_rate.is_finite() || rule.monthly_rate <= 0.0 || rule.grace_days < 0 || !cap_valid {
+        return Err(CommandError::Invalid(ErrorCode::InvalidPenaltyRule));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_penalty_rule(&mut tx, rule.coop_id).await?;
+
+    sqlx::query(
+        "INSERT INTO penalty_rules (coop_id, monthly_rate, compound, grace_days, cap_rate) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(coop_id) DO UPDATE SET
+            monthly_rate = excluded.monthly_rate,
+            compound = excluded.compound,
+            grace_days = excluded.grace_days,
+            cap_rate = excluded.cap_rate,
+            updated_at = CURRENT_TIMESTAMP"
+    )
+    .bind(rule.coop_id)
+    .bind(rule.monthly_rate)
+    .bind(rule.compound)
+    .bind(rule.grace_days)
+    .bind(rule.cap_rate)
+    .execute(&mut *tx)
+    .await?;
+
+    audit::record(&mut tx, "set_penalty_rule", "penalty_rule", rule.coop_id, before.as_ref().map(audit::snapshot), Some(audit::snapshot(&rule))).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Computes the late fee of every overdue due in the cooperative as of `as_of`.
+/// With `post`, the part not charged yet is added as a penalty due for the
+/// member, falling due on `as_of`.
+#[tauri::command]
+pub async fn calculate_penalties(state: State<'_, AppState>, coop_id: i64, as_of: NaiveDate, post: bool) -> Result<Vec<PenaltyLine>, CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let rule = fetch_penalty_rule(&mut tx, coop_id)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::PenaltyRule))?;
+
+    // Late fees themselves do not accrue further fees. Due dates are compared
+    // once parsed, so a malformed one is reported instead of sorting as text.
+    let open_dues = sqlx::query(
+        "SELECT d.id, d.coop_member_id, m.full_name, d.period, COALESCE(d.due_date, d.period) AS due_date,
+                d.amount - d.paid_amount AS outstanding,
+                (SELECT COALESCE(SUM(p.amount), 0) FROM dues p WHERE p.penalty_of = d.id AND p.deleted_at IS NULL) AS already_charged
+         FROM dues d
+         JOIN cooperative_members cm ON cm.id = d.coop_member_id
+         JOIN members m ON m.id = cm.member_id
+         WHERE cm.coop_id = ? AND d.kind != 'penalty' AND d.status != 'paid' AND d.deleted_at IS NULL
+         ORDER BY m.full_name ASC, d.period ASC, d.id ASC"
+    )
+    .bind(coop_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut lines = Vec::new();
+    for row in open_dues {
+        let due_date: String = row.try_get("due_date")?;
+        let due_day = dates::parse(&due_date).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
+        if due_day >= as_of {
+            continue;
+        }
+        let days_late = (as_of - due_day).num_days();
+        let outstanding: Money = row.try_get("outstanding")?;
+
+        let penalty = penalties::late_fee(&rule, outstanding, days_late);
+        if !penalty.is_positive() {
+            continue;
+        }
+
+        let already_charged: Money = row.try_get("already_charged")?;
+        let to_charge = (penalty - already_charged).max(Money::ZERO);
+
+        lines.push(PenaltyLine {
+            due_id: row.try_get("id")?,
+            coop_member_id: row.try_get("coop_member_id")?,
+            member_full_name: row.try_get("full_name")?,
+            period: row.try_get("period")?,
+            due_date,
+            outstanding,
+            days_late,
+            months_late: penalties::overdue_months(days_late),
+            penalty,
+            already_charged,
+            to_charge,
+            penalty_due_id: None,
+        });
+    }
+
+    if post {
+        // Like every due, a late fee's period is a month start; the day it is charged is its due date.
+        let period = dates::month_start(as_of).to_string();
+        let charged_on = as_of.to_string();
+        for line in lines.iter_mut().filter(|l| l.to_charge.is_positive()) {
+            let result = sqlx::query(
+                "INSERT INTO dues (coop_member_id, period, amount, status, due_date, penalty_of, kind, description)
+                 VALUES (?, ?, ?, 'unpaid', ?, ?, 'penalty', ?)"
+            )
+            .bind(line.coop_member_id)
+            .bind(&period)
+            .bind(line.to_charge)
+            .bind(&charged_on)
+            .bind(line.due_id)
+            .bind(format!("Gecikme zammı ({} dönemi)", line.period))
+            .execute(&mut *tx)
+            .await?;
+
+            line.penalty_due_id = Some(result.last_insert_rowid());
+            audit_due(&mut tx, "calculate_penalties", result.last_insert_rowid(), None).await?;
+        }
+
+        let mut members: Vec<i64> = lines.iter().filter(|l| l.penalty_due_id.is_some()).map(|l| l.coop_member_id).collect();
+        members.sort_unstable();
+        members.dedup();
+        for coop_member_id in members {
+            apply_member_credit(&mut tx, coop_member_id, &charged_on, "calculate_penalties").await?;
+        }
+
+        tx.commit().await?;
+    }
+
+    Ok(lines)
+}
+
+/// Payment method of ledger entries that move money between a due and the
+/// member's credit rather than recording a collection.
+const CREDIT_METHOD: &str = "credit";
+
+fn today() -> String {
+    chrono::Local::now().date_naive().format("%Y-%m-%d").to_string()
+}
+
+/// Dues are only added for members who have not left the cooperative.
+async fn ensure_active_membership(conn: &mut SqliteConnection, coop_member_id: i64) -> Result<(), CommandError> {
+    let membership = sqlx::query("SELECT exit_date FROM cooperative_members WHERE id = ?")
+        .bind(coop_member_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::CoopMember))?;
+
+    let exit_date: Option<String> = membership.try_get("exit_date")?;
+    match exit_date {
+        Some(exit_date) => Err(CommandError::Conflict(ErrorCode::MemberLeft { exit_date })),
+        None => Ok(()),
+    }
+}
+
+async fn credit_balance(conn: &mut SqliteConnection, coop_member_id: i64) -> Result<Money, CommandError> {
+    sqlx::query("SELECT COALESCE(SUM(amount), 0) AS balance FROM member_credits WHERE coop_member_id = ?")
+        .bind(coop_member_id)
+        .fetch_one(&mut *conn)
+        .await?
+        .try_get("balance")
+        .map_err(CommandError::from)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn insert_payment(
+    conn: &mut SqliteConnection,
+    command: &str,
+    due_id: i64,
+    amount: Money,
+    payment_date: &str,
+    method: Option<&str>,
+    receipt_no: Option<&str>,
+    created_by: Option<&str>,
+) -> Result<i64, CommandError> {
+    let result = sqlx::query(
+        "INSERT INTO payments (due_id, amount, payment_date, method, receipt_no, created_by)
+         VALUES (?, ?, ?, COALESCE(?, 'cash'), ?, ?)"
+    )
+    .bind(due_id)
+    .bind(amount)
+    .bind(payment_date)
+    .bind(method)
+    .bind(receipt_no)
+    .bind(created_by)
+    .execute(&mut *conn)
+    .await?;
+
+    let id = result.last_insert_rowid();
+    let payment = fetch_payment(conn, id).await?;
+    audit::record(conn, command, "payment", id, None, payment.as_ref().map(audit::snapshot)).await?;
+
+    Ok(id)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn insert_credit_entry(
+    conn: &mut SqliteConnection,
+    command: &str,
+    coop_member_id: i64,
+    amount: Money,
+    entry_type: &str,
+    entry_date: &str,
+    payment_id: Option<i64>,
+    note: Option<&str>,
+    receipt_no: Option<&str>,
+    created_by: Option<&str>,
+) -> Result<i64, CommandError> {
+    let result = sqlx::query(
+        "INSERT INTO member_credits (coop_member_id, amount, entry_type, entry_date, payment_id, note, receipt_no, created_by)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(coop_member_id)
+    .bind(amount)
+    .bind(entry_type)
+    .bind(entry_date)
+    .bind(payment_id)
+    .bind(note)
+    .bind(receipt_no)
+    .bind(created_by)
+    .execute(&mut *conn)
+    .await?;
+
+    let id = result.last_insert_rowid();
+    let after = serde_json::json!({
+        "id": id,
+        "coop_member_id": coop_member_id,
+        "amount": amount,
+        "entry_type": entry_type,
+        "entry_date": entry_date,
+        "payment_id": payment_id,
+        "note": note,
+        "receipt_no": receipt_no,
+        "created_by": created_by,
+    });
+    audit::record(conn, command, "member_credit", id, None, Some(after)).await?;
+
+    Ok(id)
+}
+
+/// Moves `amount` from the member's credit onto a due (or back, when negative)
+/// as a pair of matching payment and credit entries.
+async fn transfer_credit(
+    conn: &mut SqliteConnection,
+    command: &str,
+    due_id: i64,
+    coop_member_id: i64,
+    amount: Money,
+    date: &str,
+    note: Option<&str>,
+) -> Result<(), CommandError> {
+    let (entry_type, default_note) = if amount.is_positive() {
+        ("applied", "Üye alacağından mahsup edildi")
+    } else {
+        ("overpayment", "Fazla ödeme üye alacağına aktarıldı")
+    };
+    let note = note.unwrap_or(default_note);
+
+    let result = sqlx::query(
+        "INSERT INTO payments (due_id, amount, payment_date, method, reason) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(due_id)
+    .bind(amount)
+    .bind(date)
+    .bind(CREDIT_METHOD)
+    .bind(note)
+    .execute(&mut *conn)
+    .await?;
+
+    let payment_id = result.last_insert_rowid();
+    let payment = fetch_payment(conn, payment_id).await?;
+    audit::record(conn, command, "payment", payment_id, None, payment.as_ref().map(audit::snapshot)).await?;
+
+    insert_credit_entry(conn, command, coop_member_id, -amount, entry_type, date, Some(payment_id), Some(note), None, None).await?;
+    refresh_due_totals(conn, due_id, command).await
+}
+
+/// Keeps what is paid on a due between zero and its amount: an excess moves to
+/// the member's credit, a negative balance left by a reversal is covered from
+/// it. Returns the due's `coop_member_id`.
+async fn settle_due(conn: &mut SqliteConnection, due_id: i64, date: &str, command: &str) -> Result<i64, CommandError> {
+    refresh_due_totals(conn, due_id, command).await?;
+
+    let due = sqlx::query("SELECT coop_member_id, amount, paid_amount FROM dues WHERE id = ?")
+        .bind(due_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let coop_member_id: i64 = due.try_get("coop_member_id")?;
+    let amount: Money = due.try_get("amount")?;
+    let paid_amount: Money = due.try_get("paid_amount")?;
+
+    if paid_amount > amount {
+        transfer_credit(conn, command, due_id, coop_member_id, amount - paid_amount, date, None).await?;
+    } else if paid_amount.is_negative() {
+        let missing = -paid_amount;
+        if credit_balance(conn, coop_member_id).await? < missing {
+            return Err(CommandError::Conflict(ErrorCode::CreditAlreadyUsed));
+        }
+        transfer_credit(conn, command, due_id, coop_member_id, missing, date, None).await?;
+    }
+
+    Ok(coop_member_id)
+}
+
+/// Spends the member's credit on unpaid and partially paid dues, oldest first.
+async fn apply_member_credit(conn: &mut SqliteConnection, coop_member_id: i64, date: &str, command: &str) -> Result<(), CommandError> {
+    let mut balance = credit_balance(conn, coop_member_id).await?;
+    if !balance.is_positive() {
+        return Ok(());
+    }
+
+    let open_dues = sqlx::query(
+        "SELECT id, amount, paid_amount FROM dues
+         WHERE coop_member_id = ? AND status != 'paid' AND deleted_at IS NULL
+         ORDER BY period ASC, id ASC"
+    )
+    .bind(coop_member_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for due in open_dues {
+        if !balance.is_positive() {
+            break;
+        }
+        let due_id: i64 = due.try_get("id")?;
+        let amount: Money = due.try_get("amount")?;
+        let paid_amount: Money = due.try_get("paid_amount")?;
+
+        let applied = (amount - paid_amount).min(balance);
+        if applied.is_positive() {
+            transfer_credit(conn, command, due_id, coop_member_id, applied, date, None).await?;
+            balance -= applied;
+        }
+    }
+
+    Ok(())
+}
+
+/// Recomputes the cached `paid_amount`, `status` and `payment_date` of a due
+/// from its entries in the `payments` ledger, auditing the due if they change.
+async fn refresh_due_totals(conn: &mut SqliteConnection, due_id: i64, command: &str) -> Result<(), CommandError> {
+    // Reversed payments and their voiding entries cancel out in the sum and
+    // do not count as the due's last payment date.
+    let totals = sqlx::query(
+        "SELECT d.amount,
+                COALESCE(SUM(p.amount), 0) AS paid_amount,
+                MAX(CASE
+                    WHEN p.reversal_of IS NULL
+                     AND NOT EXISTS (SELECT 1 FROM payments r WHERE r.reversal_of = p.id)
+                    THEN p.payment_date
+                END) AS payment_date
+         FROM dues d
+         LEFT JOIN payments p ON p.due_id = d.id
+         WHERE d.id = ?
+         GROUP BY d.id"
+    )
+    .bind(due_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::Due))?;
+
+    let amount: Money = totals.try_get("amount")?;
+    let paid_amount: Money = totals.try_get("paid_amount")?;
+    let payment_date: Option<String> = totals.try_get("payment_date")?;
+
+    let status = if paid_amount.is_positive() && paid_amount >= amount {
+        "paid"
+    } else if paid_amount.is_positive() {
+        "partial"
+    } else {
+        "unpaid"
+    };
+
+    let before = fetch_due(conn, due_id).await?;
+    let unchanged = before.as_ref().is_some_and(|due| {
+        due.paid_amount == paid_amount && due.status == status && due.payment_date == payment_date
+    });
+    if unchanged {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE dues SET paid_amount = ?, status = ?, payment_date = ? WHERE id = ?")
+        .bind(paid_amount)
+        .bind(status)
+        .bind(&payment_date)
+        .bind(due_id)
+        .execute(&mut *conn)
+        .await?;
+
+    audit_due(conn, command, due_id, before).await
+}
+
+async fn fetch_due(conn: &mut SqliteConnection, id: i64) -> Result<Option<Due>, CommandError> {
+    sqlx::query_as::<_, Due>(
+        "SELECT id, coop_member_id, period, amount, paid_amount, status, payment_date,
+                COALESCE(due_date, period) AS due_date, penalty_of, kind, description,
+                deleted_at, deleted_reason
+         FROM dues
+         WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(CommandError::from)
+}
+
+async fn fetch_payment(conn: &mut SqliteConnection, id: i64) -> Result<Option<Payment>, CommandError> {
+    sqlx::query_as::<_, Payment>(
+        "SELECT p.id, p.due_id, p.amount, p.payment_date, p.method, p.receipt_no, p.created_by, p.created_at,
+                p.reversal_of, r.id AS reversed_by, p.reason
+         FROM payments p
+         LEFT JOIN payments r ON r.reversal_of = p.id
+         WHERE p.id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(CommandError::from)
+}
+
+async fn fetch_penalty_rule(conn: &mut SqliteConnection, coop_id: i64) -> Result<Option<PenaltyRule>, CommandError> {
+    sqlx::query_as::<_, PenaltyRule>(
+        "SELECT coop_id, monthly_rate, compound, grace_days, cap_rate FROM penalty_rules WHERE coop_id = ?"
+    )
+    .bind(coop_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(CommandError::from)
+}
+
+async fn fetch_member(conn: &mut SqliteConnection, id: i64) -> Result<Option<Member>, CommandError> {
+    sqlx::query_as::<_, Member>(
+        "SELECT id, tc_number, full_name, phone_1, phone_2, registration_date,
+                province, district, neighbourhood, address, email, iban, birth_date, occupation, notes, created_at
+         FROM members WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(CommandError::from)
+}
+
+/// Audits a due as it is now against its state before the write (`None` for a new due).
+async fn audit_due(conn: &mut SqliteConnection, command: &str, due_id: i64, before: Option<Due>) -> Result<(), CommandError> {
+    let after = fetch_due(conn, due_id).await?;
+    audit::record(conn, command, "due", due_id, before.as_ref().map(audit::snapshot), after.as_ref().map(audit::snapshot)).await
+}
+
+/// Marks a due as deleted, keeping it and its payments for `restore_due`.
+/// Returns the due's `coop_member_id`.
+async fn soft_delete_due(
+    conn: &mut SqliteConnection,
+    command: &str,
+    due_id: i64,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<i64, CommandError> {
+    let before = fetch_due(conn, due_id)
+        .await?
+        .filter(|due| due.deleted_at.is_none())
+        .ok_or(CommandError::NotFound(Entity::Due))?;
+
+    let has_payments = sqlx::query("SELECT id FROM payments WHERE due_id = ? LIMIT 1")
+        .bind(due_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+    if has_payments && !force {
+        return Err(CommandError::Conflict(ErrorCode::DueHasPayments { period: before.period }));
+    }
+
+    // The member keeps what was paid; a restored due is settled from credit again.
+    if before.paid_amount.is_positive() {
+        transfer_credit(conn, command, due_id, before.coop_member_id, -before.paid_amount, &today(), Some("Silinen aidata yapılan ödeme üye alacağına aktarıldı")).await?;
+    }
+
+    sqlx::query("UPDATE dues SET deleted_at = CURRENT_TIMESTAMP, deleted_reason = ? WHERE id = ?")
+        .bind(reason.map(str::trim).filter(|r| !r.is_empty()))
+        .bind(due_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let coop_member_id = before.coop_member_id;
+    audit_due(conn, command, due_id, Some(before)).await?;
+    Ok(coop_member_id)
+}
+
+#[tauri::command]
+pub async fn get_audit_log(state: State<'_, AppState>, filter: AuditLogFilter) -> Result<Vec<AuditEntry>, CommandError> {
+    let entries = sqlx::query_as::<_, AuditEntry>(
+        "SELECT id, command, entity, entity_id, before_json, after_json, created_at
+         FROM audit_log
+         WHERE (?1 IS NULL OR entity = ?1)
+           AND (?2 IS NULL OR entity_id = ?2)
+           AND (?3 IS NULL OR date(created_at, 'localtime') >= ?3)
+           AND (?4 IS NULL OR date(created_at, 'localtime') <= ?4)
+         ORDER BY id DESC
+         LIMIT ?5"
+    )
+    .bind(&filter.entity)
+    .bind(filter.entity_id)
+    .bind(filter.from.map(|d| d.to_string()))
+    .bind(filter.to.map(|d| d.to_string()))
+    .bind(filter.limit.unwrap_or(500))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn add_members_to_coop(
+    state: State<'_, AppState>,
+    args: AddMemberToCoopArgs
+) -> Result<AddMembersResult, CommandError> {
+    let entry_date = args.entry_date.to_string();
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+
+    // Start a transaction
+    let mut tx = state.db.begin().await?;
+
+    for member_id in args.member_ids {
+        let existing = sqlx::query(
+            "SELECT id FROM cooperative_members WHERE coop_id = ? AND member_id = ? AND exit_date IS NULL"
+        )
+        .bind(args.coop_id)
+        .bind(member_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if existing.is_some() {
+            skipped.push(member_id);
+            continue;
+        }
+
+        let result = sqlx::query(
+            "INSERT INTO cooperative_members (coop_id, member_id, entry_date) VALUES (?, ?, ?)"
+        )
+        .bind(args.coop_id)
+        .bind(member_id)
+        .bind(&entry_date)
+        .execute(&mut *tx)
+        .await?;
+
+        let id = result.last_insert_rowid();
+        let after = serde_json::json!({
+            "id": id,
+            "coop_id": args.coop_id,
+            "member_id": member_id,
+            "entry_date": entry_date,
+        });
+        audit::record(&mut tx, "add_members_to_coop", "coop_member", id, None, Some(after)).await?;
+        added.push(member_id);
+    }
+
+    tx.commit().await?;
+    Ok(AddMembersResult { added, skipped })
+}
+
+/// Records a member's exit. Dues of later periods are moved to the recycle
+/// bin, with anything paid on them going to the member's credit, and the
+/// final settlement is returned.
+#[tauri::command]
+pub async fn leave_coop(state: State<'_, AppState>, args: LeaveCoopArgs) -> Result<ExitSettlement, CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let membership = sqlx::query(
+        "SELECT coop_id, member_id, entry_date, exit_date FROM cooperative_members WHERE id = ?"
+    )
+    .bind(args.coop_member_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::CoopMember))?;
+
+    let exit_date: Option<String> = membership.try_get("exit_date")?;
+    if exit_date.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::AlreadyLeft));
+    }
+    let exit_date = args.exit_date.to_string();
+    let entry_date: String = membership.try_get("entry_date")?;
+    let entry_day = dates::parse(&entry_date).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
+    if args.exit_date < entry_day {
+        return Err(CommandError::Invalid(ErrorCode::DateBeforeEntry));
+    }
+
+    // Dues of the exit month stay; later ones go to the recycle bin.
+    let later_dues: Vec<i64> = sqlx::query(
+        "SELECT id FROM dues WHERE coop_member_id = ? AND period > ? AND deleted_at IS NULL"
+    )
+    .bind(args.coop_member_id)
+    .bind(dates::month_start(args.exit_date).to_string())
+    .fetch_all(&mut *tx)
+    .await?
+    .iter()
+    .map(|row| row.try_get("id").unwrap_or_default())
+    .collect();
+    for due_id in later_dues {
+        soft_delete_due(&mut tx, "leave_coop", due_id, Some("Üyelikten çıkış"), true).await?;
+    }
+
+    let note = args.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    sqlx::query("UPDATE cooperative_members SET exit_date = ?, exit_reason = ?, exit_note = ? WHERE id = ?")
+        .bind(&exit_date)
+        .bind(args.exit_reason)
+        .bind(note)
+        .bind(args.coop_member_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let coop_id: i64 = membership.try_get("coop_id")?;
+    let member_id: i64 = membership.try_get("member_id")?;
+    let after = serde_json::json!({
+        "id": args.coop_member_id,
+        "coop_id": coop_id,
+        "member_id": member_id,
+        "entry_date": entry_date,
+        "exit_date": exit_date,
+        "exit_reason": args.exit_reason,
+        "exit_note": note,
+    });
+    let before = serde_json::json!({
+        "id": args.coop_member_id,
+        "coop_id": coop_id,
+        "member_id": member_id,
+        "entry_date": entry_date,
+    });
+    audit::record(&mut tx, "leave_coop", "coop_member", args.coop_member_id, Some(before), Some(after)).await?;
+
+    // Credit freed from later dues first settles what is still owed.
+    apply_member_credit(&mut tx, args.coop_member_id, &exit_date, "leave_coop").await?;
+
+    let settlement = exit_settlement(&mut tx, args.coop_member_id).await?;
+
+    tx.commit().await?;
+    Ok(settlement)
+}
+
+#[tauri::command]
+pub async fn get_exit_settlement(state: State<'_, AppState>, coop_member_id: i64) -> Result<ExitSettlement, CommandError> {
+    let mut conn = state.db.acquire().await?;
+    exit_settlement(&mut conn, coop_member_id).await
+}
+
+#[tauri::command]
+pub async fn set_exit_refund_rate(state: State<'_, AppState>, coop_id: i64, exit_refund_rate: f64) -> Result<(), CommandError> {
+    if !(0.0..=100.0).contains(&exit_refund_rate) {
+        return Err(CommandError::Invalid(ErrorCode::RefundRateOutOfRange));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let before: f64 = sqlx::query("SELECT exit_refund_rate FROM cooperatives WHERE id = ?")
+        .bind(coop_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::Cooperative))?
+        .try_get("exit_refund_rate")?;
+
+    sqlx::query("UPDATE cooperatives SET exit_refund_rate = ? WHERE id = ?")
+        .bind(exit_refund_rate)
+        .bind(coop_id)
+        .execute(&mut *tx)
+        .await?;
+
+    audit::record(
+        &mut tx,
+        "set_exit_refund_rate",
+        "cooperative",
+        coop_id,
+        Some(serde_json::json!({ "exit_refund_rate": before })),
+        Some(serde_json::json!({ "exit_refund_rate": exit_refund_rate })),
+    ).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// What a leaving member paid and owes, and what the cooperative pays back:
+/// `exit_refund_rate` percent of what was paid on dues other than late fees,
+/// plus any unused credit, less the outstanding debt.
+async fn exit_settlement(conn: &mut SqliteConnection, coop_member_id: i64) -> Result<ExitSettlement, CommandError> {
+    let row = sqlx::query(
+        "SELECT cm.id, m.full_name, cm.entry_date, cm.exit_date, cm.exit_reason, c.exit_refund_rate,
+                COALESCE(SUM(d.amount), 0) AS total_billed,
+                COALESCE(SUM(d.paid_amount), 0) AS total_paid,
+                COALESCE(SUM(CASE WHEN d.kind != 'penalty' THEN d.paid_amount ELSE 0 END), 0) AS refundable_paid
+         FROM cooperative_members cm
+         JOIN members m ON m.id = cm.member_id
+         JOIN cooperatives c ON c.id = cm.coop_id
+         LEFT JOIN dues d ON d.coop_member_id = cm.id AND d.deleted_at IS NULL
+         WHERE cm.id = ?
+         GROUP BY cm.id"
+    )
+    .bind(coop_member_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::CoopMember))?;
+
+    let total_billed: Money = row.try_get("total_billed")?;
+    let total_paid: Money = row.try_get("total_paid")?;
+    let refundable_paid: Money = row.try_get("refundable_paid")?;
+    let exit_refund_rate: f64 = row.try_get("exit_refund_rate")?;
+
+    let outstanding = total_billed - total_paid;
+    let credit_balance = credit_balance(conn, coop_member_id).await?;
+    let refund_amount = refundable_paid.scale(exit_refund_rate / 100.0);
+
+    Ok(ExitSettlement {
+        coop_member_id,
+        member_full_name: row.try_get("full_name")?,
+        entry_date: row.try_get("entry_date")?,
+        exit_date: row.try_get("exit_date")?,
+        exit_reason: row.try_get("exit_reason")?,
+        total_billed,
+        total_paid,
+        outstanding,
+        credit_balance,
+        exit_refund_rate,
+        refundable_paid,
+        refund_amount,
+        net_amount: refund_amount + credit_balance - outstanding,
+    })
+}
+
+/// Hands a membership over to another member (devir). The buyer gets a new
+/// membership row that takes over the outstanding dues and the seller's
+/// credit; paid dues stay with the seller's row and the two are linked in
+/// `membership_transfers`. Returns the buyer's `coop_member_id`.
+#[tauri::command]
+pub async fn transfer_membership(
+    state: State<'_, AppState>,
+    coop_member_id: i64,
+    new_member_id: i64,
+    date: NaiveDate,
+    note: Option<String>
+) -> Result<i64, CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let membership = sqlx::query(
+        "SELECT coop_id, member_id, entry_date, exit_date FROM cooperative_members WHERE id = ?"
+    )
+    .bind(coop_member_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CommandError::NotFound(Entity::CoopMember))?;
+
+    let coop_id: i64 = membership.try_get("coop_id")?;
+    let member_id: i64 = membership.try_get("member_id")?;
+    let entry_date: String = membership.try_get("entry_date")?;
+    let exit_date: Option<String> = membership.try_get("exit_date")?;
+    if exit_date.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::AlreadyLeft));
+    }
+    let entry_day = dates::parse(&entry_date).ok_or(CommandError::Invalid(ErrorCode::InvalidStoredDate))?;
+    if date < entry_day {
+        return Err(CommandError::Invalid(ErrorCode::DateBeforeEntry));
+    }
+    let date = date.to_string();
+    if new_member_id == member_id {
+        return Err(CommandError::Invalid(ErrorCode::SameMemberTransfer));
+    }
+
+    let buyer = fetch_member(&mut tx, new_member_id).await?.ok_or(CommandError::NotFound(Entity::Member))?;
+    let already_member = sqlx::query(
+        "SELECT id FROM cooperative_members WHERE coop_id = ? AND member_id = ? AND exit_date IS NULL"
+    )
+    .bind(coop_id)
+    .bind(new_member_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if already_member.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::AlreadyMember));
+    }
+
+    // 1. Open the buyer's membership
+    let result = sqlx::query(
+        "INSERT INTO cooperative_members (coop_id, member_id, entry_date) VALUES (?, ?, ?)"
+    )
+    .bind(coop_id)
+    .bind(new_member_id)
+    .bind(&date)
+    .execute(&mut *tx)
+    .await?;
+
+    let new_coop_member_id = result.last_insert_rowid();
+    let after = serde_json::json!({
+        "id": new_coop_member_id,
+        "coop_id": coop_id,
+        "member_id": new_member_id,
+        "entry_date": date,
+    });
+    audit::record(&mut tx, "transfer_membership", "coop_member", new_coop_member_id, None, Some(after)).await?;
+
+    // 2. The buyer inherits the debt, including what is left of partially paid dues
+    let open_dues: Vec<i64> = sqlx::query(
+        "SELECT id FROM dues WHERE coop_member_id = ? AND status != 'paid' AND deleted_at IS NULL"
+    )
+    .bind(coop_member_id)
+    .fetch_all(&mut *tx)
+    .await?
+    .iter()
+    .map(|row| row.try_get("id").unwrap_or_default())
+    .collect();
+
+    for due_id in open_dues {
+        let before = fetch_due(&mut tx, due_id).await?;
+        sqlx::query("UPDATE dues SET coop_member_id = ? WHERE id = ?")
+            .bind(new_coop_member_id)
+            .bind(due_id)
+            .execute(&mut *tx)
+            .await?;
+        audit_due(&mut tx, "transfer_membership", due_id, before).await?;
+    }
+
+    // 3. ...and whatever credit the seller had
+    let credit = credit_balance(&mut tx, coop_member_id).await?;
+    if credit.is_positive() {
+        let credit_note = format!("Üyelik devri: {}", buyer.full_name);
+        insert_credit_entry(&mut tx, "transfer_membership", coop_member_id, -credit, "transfer", &date, None, Some(&credit_note), None, None).await?;
+        insert_credit_entry(&mut tx, "transfer_membership", new_coop_member_id, credit, "transfer", &date, None, Some(&credit_note), None, None).await?;
+        apply_member_credit(&mut tx, new_coop_member_id, &date, "transfer_membership").await?;
+    }
+
+    // 4. Close the seller's membership and record the hand-over
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    let exit_note = note.map(str::to_string).unwrap_or_else(|| format!("Üyelik devri: {}", buyer.full_name));
+    sqlx::query("UPDATE cooperative_members SET exit_date = ?, exit_reason = 'other', exit_note = ? WHERE id = ?")
+        .bind(&date)
+        .bind(&exit_note)
+        .bind(coop_member_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let before = serde_json::json!({
+        "id": coop_member_id,
+        "coop_id": coop_id,
+        "member_id": member_id,
+        "entry_date": entry_date,
+    });
+    let after = serde_json::json!({
+        "id": coop_member_id,
+        "coop_id": coop_id,
+        "member_id": member_id,
+        "entry_date": entry_date,
+        "exit_date": date,
+        "exit_reason": "other",
+        "exit_note": exit_note,
+    });
+    audit::record(&mut tx, "transfer_membership", "coop_member", coop_member_id, Some(before), Some(after)).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO membership_transfers (coop_id, from_coop_member_id, to_coop_member_id, transfer_date, note)
+         VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(coop_id)
+    .bind(coop_member_id)
+    .bind(new_coop_member_id)
+    .bind(&date)
+    .bind(note)
+    .execute(&mut *tx)
+    .await?;
+
+    let after = serde_json::json!({
+        "id": result.last_insert_rowid(),
+        "coop_id": coop_id,
+        "from_coop_member_id": coop_member_id,
+        "to_coop_member_id": new_coop_member_id,
+        "transfer_date": date,
+        "note": note,
+    });
+    audit::record(&mut tx, "transfer_membership", "membership_transfer", result.last_insert_rowid(), None, Some(after)).await?;
+
+    tx.commit().await?;
+    Ok(new_coop_member_id)
+}
+
+/// Every holder of a membership, from the original member to the current
+/// one, whichever row of the chain `coop_member_id` points at.
+#[tauri::command]
+pub async fn get_ownership_chain(state: State<'_, AppState>, coop_member_id: i64) -> Result<Vec<OwnershipLink>, CommandError> {
+    let chain = sqlx::query_as::<_, OwnershipLink>(
+        "WITH RECURSIVE
+            earlier(id) AS (
+                SELECT ?1
+                UNION
+                SELECT t.from_coop_member_id FROM membership_transfers t JOIN earlier e ON t.to_coop_member_id = e.id
+            ),
+            later(id) AS (
+                SELECT ?1
+                UNION
+                SELECT t.to_coop_member_id FROM membership_transfers t JOIN later l ON t.from_coop_member_id = l.id
+            )
+         SELECT cm.id AS coop_member_id, cm.member_id, m.full_name, m.tc_number, cm.entry_date, cm.exit_date
+         FROM cooperative_members cm
+         JOIN members m ON m.id = cm.member_id
+         WHERE cm.id IN (SELECT id FROM earlier UNION SELECT id FROM later)
+         ORDER BY cm.entry_date ASC, cm.id ASC"
+    )
+    .bind(coop_member_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(chain)
+}
+
+#[tauri::command]
+pub async fn get_coop_members(state: State<'_, AppState>, coop_id: i64) -> Result<Vec<CoopMember>, CommandError> {
+    let members = sqlx::query_as::<_, CoopMember>(
+        "SELECT 
+            cm.id, cm.member_id, m.full_name, m.tc_number, m.phone_1, cm.entry_date,
+            cm.exit_date, cm.exit_reason,
+            tf.from_coop_member_id AS transferred_from, tt.to_coop_member_id AS transferred_to
+         FROM cooperative_members cm
+         JOIN members m ON cm.member_id = m.id
+         LEFT JOIN membership_transfers tf ON tf.to_coop_member_id = cm.id
+         LEFT JOIN membership_transfers tt ON tt.from_coop_member_id = cm.id
+         WHERE cm.coop_id = ?
+         ORDER BY cm.exit_date IS NOT NULL, m.full_name ASC"
+    )
+    .bind(coop_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(members)
+}
+
+#[tauri::command]
+pub async fn get_available_members(state: State<'_, AppState>, coop_id: i64) -> Result<Vec<Member>, CommandError> {
+    let members = sqlx::query_as::<_, Member>(
+        "SELECT * FROM members 
+         WHERE id NOT IN (SELECT member_id FROM cooperative_members WHERE coop_id = ? AND exit_date IS NULL)
+         ORDER BY full_name ASC"
+    )
+    .bind(coop_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(members)
+}
+
+#[tauri::command]
+pub async fn get_coop_details(state: State<'_, AppState>, id: i64) -> Result<Cooperative, CommandError> {
+    let mut conn = state.db.acquire().await?;
+    let coop = fetch_coop(&mut conn, id).await?.ok_or(CommandError::NotFound(Entity::Cooperative))?;
+
+    Ok(coop)
+}
+
+#[tauri::command]
+pub async fn create_coop(
+    state: State<'_, AppState>,
+    coop: CreateCoopArgs
+) -> Result<i64, CommandError> {
+    if coop.name.trim().is_empty() {
+        return Err(CommandError::Validation(vec![
+            FieldError::new("name", "required", "Cooperative name is required", "Kooperatif adı zorunludur."),
+        ]));
+    }
+    let exit_refund_rate = coop.exit_refund_rate.unwrap_or(0.0);
+    if !(0.0..=100.0).contains(&exit_refund_rate) {
+        return Err(CommandError::Invalid(ErrorCode::RefundRateOutOfRange));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let result = sqlx::query(
+        "INSERT INTO cooperatives (name, start_date, exit_refund_rate) VALUES (?, ?, ?)"
+    )
+    .bind(coop.name.trim())
+    .bind(coop.start_date.to_string())
+    .bind(exit_refund_rate)
+    .execute(&mut *tx)
+    .await?;
+
+    let id = result.last_insert_rowid();
+    let after = fetch_coop(&mut tx, id).await?;
+    audit::record(&mut tx, "create_coop", "cooperative", id, None, after.as_ref().map(audit::snapshot)).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_coop(
+    state: State<'_, AppState>,
+    id: i64,
+    coop: CreateCoopArgs
+) -> Result<(), CommandError> {
+    if coop.name.trim().is_empty() {
+        return Err(CommandError::Validation(vec![
+            FieldError::new("name", "required", "Cooperative name is required", "Kooperatif adı zorunludur."),
+        ]));
+    }
+    if let Some(rate) = coop.exit_refund_rate {
+        if !(0.0..=100.0).contains(&rate) {
+            return Err(CommandError::Invalid(ErrorCode::RefundRateOutOfRange));
+        }
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_coop(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Cooperative))?;
+
+    // Leaving out the refund rate keeps the current one.
+    sqlx::query(
+        "UPDATE cooperatives SET name = ?, start_date = ?, exit_refund_rate = COALESCE(?, exit_refund_rate) WHERE id = ?"
+    )
+    .bind(coop.name.trim())
+    .bind(coop.start_date.to_string())
+    .bind(coop.exit_refund_rate)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    let after = fetch_coop(&mut tx, id).await?;
+    audit::record(&mut tx, "update_coop", "cooperative", id, Some(audit::snapshot(&before)), after.as_ref().map(audit::snapshot)).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Sets a cooperative's status; anything but `active` hides it from the
+/// default `get_coops` listing.
+#[tauri::command]
+pub async fn archive_coop(state: State<'_, AppState>, id: i64, status: CoopStatus) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_coop(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Cooperative))?;
+
+    sqlx::query("UPDATE cooperatives SET status = ? WHERE id = ?")
+        .bind(status)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    let after = fetch_coop(&mut tx, id).await?;
+    audit::record(&mut tx, "archive_coop", "cooperative", id, Some(audit::snapshot(&before)), after.as_ref().map(audit::snapshot)).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Deletes a cooperative that was created by mistake. Once it has members,
+/// and therefore possibly dues, it can only be archived.
+#[tauri::command]
+pub async fn delete_coop(state: State<'_, AppState>, id: i64) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_coop(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Cooperative))?;
+
+    let usage = sqlx::query(
+        "SELECT
+            (SELECT COUNT(*) FROM cooperative_members WHERE coop_id = ?1) AS member_count,
+            (SELECT COUNT(*) FROM dues d JOIN cooperative_members cm ON cm.id = d.coop_member_id WHERE cm.coop_id = ?1) AS due_count"
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let member_count: i64 = usage.try_get("member_count")?;
+    let due_count: i64 = usage.try_get("due_count")?;
+    if member_count > 0 || due_count > 0 {
+        return Err(CommandError::Conflict(ErrorCode::CoopInUse { members: member_count, dues: due_count }));
+    }
+
+    sqlx::query("DELETE FROM penalty_rules WHERE coop_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM dues_plans WHERE coop_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM cooperatives WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    audit::record(&mut tx, "delete_coop", "cooperative", id, Some(audit::snapshot(&before)), None).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_coops(state: State<'_, AppState>, include_archived: Option<bool>) -> Result<Vec<Cooperative>, CommandError> {
+    let coops = sqlx::query_as::<_, Cooperative>(
+        "SELECT id, name, start_date, exit_refund_rate, status, created_at FROM cooperatives
+         WHERE status = 'active' OR ?
+         ORDER BY start_date DESC"
+    )
+    .bind(include_archived.unwrap_or(false))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(coops)
+}
+
+async fn fetch_coop(conn: &mut SqliteConnection, id: i64) -> Result<Option<Cooperative>, CommandError> {
+    sqlx::query_as::<_, Cooperative>(
+        "SELECT id, name, start_date, exit_refund_rate, status, created_at FROM cooperatives WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn create_member(
+    state: State<'_, AppState>,
+    member: CreateMemberArgs
+) -> Result<i64, CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let member = validate_member(&mut tx, None, member).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO members (
+            tc_number, full_name, phone_1, phone_2, registration_date,
+            province, district, neighbourhood, address, email, iban, birth_date, occupation, notes
+         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(member.tc_number)
+    .bind(member.full_name)
+    .bind(member.phone_1)
+    .bind(member.phone_2)
+    .bind(member.registration_date.to_string())
+    .bind(member.province)
+    .bind(member.district)
+    .bind(member.neighbourhood)
+    .bind(member.address)
+    .bind(member.email)
+    .bind(member.iban)
+    .bind(member.birth_date.map(|d| d.to_string()))
+    .bind(member.occupation)
+    .bind(member.notes)
+    .execute(&mut *tx)
+    .await?;
+
+    let id = result.last_insert_rowid();
+    search::index_member(&mut tx, id).await?;
+    let after = fetch_member(&mut tx, id).await?;
+    audit::record(&mut tx, "create_member", "member", id, None, after.as_ref().map(audit::snapshot)).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn get_members(state: State<'_, AppState>) -> Result<Vec<Member>, CommandError> {
+    let members = sqlx::query_as::<_, Member>(
+        "SELECT id, tc_number, full_name, phone_1, phone_2, registration_date,
+                province, district, neighbourhood, address, email, iban, birth_date, occupation, notes, created_at
+         FROM members ORDER BY full_name ASC"
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(members)
+}
+
+/// Lists one page of members with their debt, filtered and sorted as asked,
+/// together with the number of members matching over all pages.
+#[tauri::command]
+pub async fn list_members(state: State<'_, AppState>, query: MemberListQuery) -> Result<MemberPage, CommandError> {
+    let offset = query.offset.unwrap_or(0).max(0);
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let search = query.search.as_deref().and_then(search::match_query);
+
+    let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) AS total");
+    push_member_list_source(&mut count, &query, search.clone());
+    let total: i64 = count.build().fetch_one(&state.db).await?.try_get("total")?;
+
+    let mut page = QueryBuilder::<Sqlite>::new(
+        "SELECT m.id, m.tc_number, m.full_name, m.phone_1, m.phone_2, m.registration_date,
+                m.province, m.district, m.neighbourhood, m.address, m.email, m.iban, m.birth_date, m.occupation, m.notes, m.created_at,
+                COALESCE(debts.debt, 0) AS debt"
+    );
+    let ranked = search.is_some();
+    push_member_list_source(&mut page, &query, search);
+    let direction = if query.descending { "DESC" } else { "ASC" };
+    let order = match query.sort {
+        None | Some(MemberSort::Name) => "m.full_name",
+        Some(MemberSort::RegistrationDate) => "m.registration_date",
+        Some(MemberSort::Debt) => "debt",
+    };
+    // A search always lists the best matches first; the chosen sort only breaks ties.
+    let rank = if ranked { format!("{} ASC, ", search::RANK) } else { String::new() };
+    page.push(format!(" ORDER BY {rank}{order} {direction}, m.id {direction} LIMIT "));
+    page.push_bind(limit);
+    page.push(" OFFSET ");
+    page.push_bind(offset);
+    let members = page.build_query_as::<MemberListItem>().fetch_all(&state.db).await?;
+
+    Ok(MemberPage { members, total, offset, limit })
+}
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// The `FROM` and `WHERE` clauses shared by the count and the page of `list_members`.
+fn push_member_list_source(builder: &mut QueryBuilder<'_, Sqlite>, query: &MemberListQuery, search: Option<String>) {
+    builder.push(
+        " FROM members m
+         LEFT JOIN (
+            SELECT cm.member_id, SUM(d.amount - d.paid_amount) AS debt
+            FROM dues d
+            JOIN cooperative_members cm ON cm.id = d.coop_member_id
+            WHERE d.deleted_at IS NULL AND COALESCE(d.due_date, d.period) <= "
+    );
+    builder.push_bind(today());
+    if let Some(coop_id) = query.coop_id {
+        builder.push(" AND cm.coop_id = ").push_bind(coop_id);
+    }
+    builder.push(" GROUP BY cm.member_id) debts ON debts.member_id = m.id");
+
+    // Joined rather than filtered by id so the page can be ordered by rank.
+    if let Some(search) = search {
+        builder.push(" JOIN member_search ON member_search.rowid = m.id WHERE member_search MATCH ");
+        builder.push_bind(search);
+    } else {
+        builder.push(" WHERE 1 = 1");
+    }
+
+    let membership = |builder: &mut QueryBuilder<'_, Sqlite>, exists: bool, active_only: bool| {
+        builder.push(if exists { " AND EXISTS" } else { " AND NOT EXISTS" });
+        builder.push(" (SELECT 1 FROM cooperative_members cm WHERE cm.member_id = m.id");
+        if active_only {
+            builder.push(" AND cm.exit_date IS NULL");
+        }
+        if let Some(coop_id) = query.coop_id {
+            builder.push(" AND cm.coop_id = ").push_bind(coop_id);
+        }
+        builder.push(")");
+    };
+    match query.status {
+        None if query.coop_id.is_some() => membership(builder, true, false),
+        None => {}
+        Some(MembershipStatus::Active) => membership(builder, true, true),
+        Some(MembershipStatus::Former) => {
+            membership(builder, true, false);
+            membership(builder, false, true);
+        }
+        Some(MembershipStatus::NotMember) => membership(builder, false, true),
+    }
+
+    match query.has_debt {
+        Some(true) => { builder.push(" AND COALESCE(debts.debt, 0) > 0"); }
+        Some(false) => { builder.push(" AND COALESCE(debts.debt, 0) <= 0"); }
+        None => {}
+    }
+    if let Some(from) = query.registered_from {
+        builder.push(" AND m.registration_date >= ").push_bind(from.to_string());
+    }
+    if let Some(to) = query.registered_to {
+        builder.push(" AND m.registration_date <= ").push_bind(to.to_string());
+    }
+}
 
 #[tauri::command]
 pub async fn update_member(
     state: State<'_, AppState>,
     id: i64,
     member: CreateMemberArgs
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_member(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Member))?;
+    let member = validate_member(&mut tx, Some(id), member).await?;
+
     sqlx::query(
-        "UPDATE members SET tc_number=?, full_name=?, phone_1=?, phone_2=?, registration_date=? WHERE id=?"
+        "UPDATE members SET tc_number=?, full_name=?, phone_1=?, phone_2=?, registration_date=?,
+            province=?, district=?, neighbourhood=?, address=?, email=?, iban=?, birth_date=?, occupation=?, notes=?
+         WHERE id=?"
     )
     .bind(member.tc_number)
     .bind(member.full_name)
     .bind(member.phone_1)
     .bind(member.phone_2)
-    .bind(member.registration_date)
+    .bind(member.registration_date.to_string())
+    .bind(member.province)
+    .bind(member.district)
+    .bind(member.neighbourhood)
+    .bind(member.address)
+    .bind(member.email)
+    .bind(member.iban)
+    .bind(member.birth_date.map(|d| d.to_string()))
+    .bind(member.occupation)
+    .bind(member.notes)
     .bind(id)
-    .execute(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .execute(&mut *tx)
+    .await?;
+
+    search::index_member(&mut tx, id).await?;
+    let after = fetch_member(&mut tx, id).await?;
+    audit::record(&mut tx, "update_member", "member", id, Some(audit::snapshot(&before)), after.as_ref().map(audit::snapshot)).await?;
 
+    tx.commit().await?;
     Ok(())
 }
 
+/// Normalizes a member form and checks it, reporting every invalid field at
+/// once. `id` is the member being updated, if any.
+async fn validate_member(
+    conn: &mut SqliteConnection,
+    id: Option<i64>,
+    member: CreateMemberArgs,
+) -> Result<CreateMemberArgs, CommandError> {
+    let mut fields = Vec::new();
+
+    let phone_1 = validation::normalize_phone(&member.phone_1).unwrap_or_else(|e| {
+        fields.push(FieldError::new("phone_1", e.code(), e.message(), e.message_tr()));
+        member.phone_1.clone()
+    });
+    let phone_2 = match member.phone_2.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(phone) => Some(validation::normalize_phone(phone).unwrap_or_else(|e| {
+            fields.push(FieldError::new("phone_2", e.code(), e.message(), e.message_tr()));
+            phone.to_string()
+        })),
+    };
+
+    let email = optional_text(member.email.as_deref()).map(|email| {
+        validation::normalize_email(email).unwrap_or_else(|e| {
+            fields.push(FieldError::new("email", e.code(), e.message(), e.message_tr()));
+            email.to_string()
+        })
+    });
+    let iban = optional_text(member.iban.as_deref()).map(|iban| {
+        validation::normalize_iban(iban).unwrap_or_else(|e| {
+            fields.push(FieldError::new("iban", e.code(), e.message(), e.message_tr()));
+            iban.to_string()
+        })
+    });
+    if member.birth_date.is_some_and(|d| d > chrono::Local::now().date_naive()) {
+        fields.push(FieldError::new("birth_date", "birth_date_future", "Birth date cannot be in the future", "Doğum tarihi ileri bir tarih olamaz."));
+    }
+
+    let member = CreateMemberArgs {
+        tc_number: validation::normalize_tc(&member.tc_number),
+        full_name: member.full_name.trim().to_string(),
+        phone_1,
+        phone_2,
+        province: optional_text(member.province.as_deref()).map(str::to_string),
+        district: optional_text(member.district.as_deref()).map(str::to_string),
+        neighbourhood: optional_text(member.neighbourhood.as_deref()).map(str::to_string),
+        address: optional_text(member.address.as_deref()).map(str::to_string),
+        email,
+        iban,
+        occupation: optional_text(member.occupation.as_deref()).map(str::to_string),
+        notes: optional_text(member.notes.as_deref()).map(str::to_string),
+        ..member
+    };
+
+    if let Err(e) = validation::check_tc(&member.tc_number) {
+        fields.push(FieldError::new("tc_number", e.code(), e.message(), e.message_tr()));
+    } else {
+        let taken = sqlx::query("SELECT id FROM members WHERE tc_number = ? AND id IS NOT ?")
+            .bind(&member.tc_number)
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if taken.is_some() {
+            let code = ErrorCode::TcNumberTaken;
+            fields.push(FieldError::new("tc_number", code.as_str(), &code.message(), &code.message_tr()));
+        }
+    }
+    if member.full_name.is_empty() {
+        fields.push(FieldError::new("full_name", "required", "Full name is required", "Ad soyad zorunludur."));
+    }
+
+    if fields.is_empty() {
+        Ok(member)
+    } else {
+        Err(CommandError::Validation(fields))
+    }
+}
+
+/// Trims an optional form field, treating a blank one as missing.
+fn optional_text(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Lists members whose stored TC Kimlik No fails validation.
 #[tauri::command]
-pub async fn search_members(state: State<'_, AppState>, query: String) -> Result<Vec<Member>, String> {
-    let pattern = format!("%{}%", query);
-    let members = sqlx::query_as::<_, Member>(
-        "SELECT id, tc_number, full_name, phone_1, phone_2, registration_date, created_at FROM members 
-         WHERE full_name LIKE ? OR tc_number LIKE ? 
-         ORDER BY full_name ASC"
-    )
-    .bind(&pattern)
-    .bind(&pattern)
+pub async fn find_invalid_tc_numbers(state: State<'_, AppState>) -> Result<Vec<InvalidTcNumber>, CommandError> {
+    let members = sqlx::query("SELECT id, full_name, tc_number FROM members ORDER BY full_name ASC")
+        .fetch_all(&state.db)
+        .await?;
+
+    let mut invalid = Vec::new();
+    for row in members {
+        let tc_number: String = row.try_get("tc_number")?;
+        if let Err(e) = validation::check_tc(&tc_number) {
+            invalid.push(InvalidTcNumber {
+                member_id: row.try_get("id")?,
+                full_name: row.try_get("full_name")?,
+                tc_number,
+                code: e.code().to_string(),
+                message: e.message().to_string(),
+            });
+        }
+    }
+
+    Ok(invalid)
+}
+
+/// Lists stored phone numbers that could not be brought to E.164, e.g. the
+/// ones the phone normalization migration had to leave as they were.
+#[tauri::command]
+pub async fn find_invalid_phones(state: State<'_, AppState>) -> Result<Vec<InvalidPhone>, CommandError> {
+    let members = sqlx::query("SELECT id, full_name, phone_1, phone_2 FROM members ORDER BY full_name ASC")
+        .fetch_all(&state.db)
+        .await?;
+
+    let mut invalid = Vec::new();
+    for row in members {
+        let member_id: i64 = row.try_get("id")?;
+        let full_name: String = row.try_get("full_name")?;
+        let phone_1: String = row.try_get("phone_1")?;
+        let phone_2: Option<String> = row.try_get("phone_2")?;
+
+        for (field, phone) in [("phone_1", Some(phone_1)), ("phone_2", phone_2)] {
+            let Some(phone) = phone else { continue };
+            if let Err(e) = validation::normalize_phone(&phone) {
+                invalid.push(InvalidPhone {
+                    member_id,
+                    full_name: full_name.clone(),
+                    field: field.to_string(),
+                    phone,
+                    code: e.code().to_string(),
+                    message: e.message().to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(invalid)
+}
+
+/// Lists stored dates that are not in `YYYY-MM-DD` form, e.g. values the
+/// date repair migration could not read.
+#[tauri::command]
+pub async fn find_invalid_dates(state: State<'_, AppState>) -> Result<Vec<InvalidDate>, CommandError> {
+    let mut invalid = Vec::new();
+    for (table, column) in dates::STORED_COLUMNS {
+        let rows = sqlx::query(&format!(
+            "SELECT id, CAST({column} AS TEXT) AS value FROM {table} WHERE {column} IS NOT NULL ORDER BY id"
+        ))
+        .fetch_all(&state.db)
+        .await?;
+
+        for row in rows {
+            let value: String = row.try_get("value")?;
+            if dates::repair(column, &value).as_deref() != Some(value.as_str()) {
+                invalid.push(InvalidDate {
+                    table: table.to_string(),
+                    column: column.to_string(),
+                    row_id: row.try_get("id")?,
+                    value,
+                });
+            }
+        }
+    }
+
+    Ok(invalid)
+}
+
+/// Finds members by any word of their name, TC Kimlik No, phone numbers or notes,
+/// ignoring case and Turkish letter marks, best matches first. Every word only
+/// needs to be the start of one, so "ali 0532" finds "Ali Şahin, 0532 111 22 33".
+#[tauri::command]
+pub async fn search_members(state: State<'_, AppState>, query: String) -> Result<Vec<Member>, CommandError> {
+    let Some(fts_query) = search::match_query(&query) else {
+        return get_members(state).await;
+    };
+
+    let members = sqlx::query_as::<_, Member>(&format!(
+        "SELECT m.id, m.tc_number, m.full_name, m.phone_1, m.phone_2, m.registration_date,
+                m.province, m.district, m.neighbourhood, m.address, m.email, m.iban, m.birth_date, m.occupation, m.notes, m.created_at
+         FROM member_search
+         JOIN members m ON m.id = member_search.rowid
+         WHERE member_search MATCH ?
+         ORDER BY {}, m.full_name ASC",
+        search::RANK
+    ))
+    .bind(fts_query)
     .fetch_all(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    .await?;
 
     Ok(members)
 }
+
+/// Copies a file into the document store and attaches it to a member, or to
+/// one of their memberships. Content already in the store is not copied
+/// again; attaching the same file twice to the same owner is refused.
+#[tauri::command]
+pub async fn attach_document(state: State<'_, AppState>, args: AttachDocumentArgs) -> Result<Document, CommandError> {
+    if args.member_id.is_some() == args.coop_member_id.is_some() {
+        return Err(CommandError::Invalid(ErrorCode::DocumentOwner));
+    }
+
+    let source = PathBuf::from(&args.source_path);
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| CommandError::File(format!("Not a file: {}", args.source_path)))?;
+    let (sha256, size) = documents::hash_file(&source)?;
+
+    let mut tx = state.db.begin().await?;
+
+    if let Some(member_id) = args.member_id {
+        fetch_member(&mut tx, member_id).await?.ok_or(CommandError::NotFound(Entity::Member))?;
+    }
+    if let Some(coop_member_id) = args.coop_member_id {
+        sqlx::query("SELECT id FROM cooperative_members WHERE id = ?")
+            .bind(coop_member_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(CommandError::NotFound(Entity::CoopMember))?;
+    }
+
+    let attached = sqlx::query("SELECT id FROM documents WHERE sha256 = ? AND (member_id = ? OR coop_member_id = ?)")
+        .bind(&sha256)
+        .bind(args.member_id)
+        .bind(args.coop_member_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    if attached.is_some() {
+        return Err(CommandError::Conflict(ErrorCode::DocumentExists));
+    }
+
+    let stored_name: String = match sqlx::query("SELECT stored_name FROM documents WHERE sha256 = ? LIMIT 1")
+        .bind(&sha256)
+        .fetch_optional(&mut *tx)
+        .await?
+    {
+        Some(row) => row.try_get("stored_name")?,
+        None => documents::stored_name(&sha256, &file_name),
+    };
+    let copied = documents::store(&state.data_dir, &source, &stored_name)?;
+
+    let saved: Result<Document, CommandError> = async {
+        let result = sqlx::query(
+            "INSERT INTO documents (member_id, coop_member_id, kind, file_name, stored_name, mime_type, size, sha256, note)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(args.member_id)
+        .bind(args.coop_member_id)
+        .bind(args.kind)
+        .bind(&file_name)
+        .bind(&stored_name)
+        .bind(documents::mime_type(&file_name))
+        .bind(size as i64)
+        .bind(&sha256)
+        .bind(optional_text(args.note.as_deref()))
+        .execute(&mut *tx)
+        .await?;
+
+        let id = result.last_insert_rowid();
+        let document = fetch_document(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Document))?;
+        audit::record(&mut tx, "attach_document", "document", id, None, Some(audit::snapshot(&document))).await?;
+
+        tx.commit().await?;
+        Ok(document)
+    }
+    .await;
+
+    // Don't leave behind a file no document points to.
+    if saved.is_err() && copied {
+        documents::remove(&state.data_dir, &stored_name)?;
+    }
+    saved
+}
+
+/// Documents of a member, including those attached to their memberships, or
+/// of a single membership.
+#[tauri::command]
+pub async fn get_documents(
+    state: State<'_, AppState>,
+    member_id: Option<i64>,
+    coop_member_id: Option<i64>
+) -> Result<Vec<Document>, CommandError> {
+    let documents = sqlx::query_as::<_, Document>(
+        "SELECT id, member_id, coop_member_id, kind, file_name, mime_type, size, sha256, note, created_at
+         FROM documents
+         WHERE member_id = ?
+            OR coop_member_id = ?
+            OR coop_member_id IN (SELECT id FROM cooperative_members WHERE member_id = ?)
+         ORDER BY created_at DESC, id DESC"
+    )
+    .bind(member_id)
+    .bind(coop_member_id)
+    .bind(member_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(documents)
+}
+
+/// Opens a document with the program the system uses for its file type.
+#[tauri::command]
+pub async fn open_document(state: State<'_, AppState>, id: i64) -> Result<(), CommandError> {
+    let stored_name: String = sqlx::query("SELECT stored_name FROM documents WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or(CommandError::NotFound(Entity::Document))?
+        .try_get("stored_name")?;
+
+    let path = documents::store_dir(&state.data_dir).join(stored_name);
+    tauri_plugin_opener::open_path(&path, None::<&str>).map_err(|e| CommandError::File(e.to_string()))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_document(state: State<'_, AppState>, id: i64) -> Result<(), CommandError> {
+    let mut tx = state.db.begin().await?;
+
+    let before = fetch_document(&mut tx, id).await?.ok_or(CommandError::NotFound(Entity::Document))?;
+    let stored_name: String = sqlx::query("SELECT stored_name FROM documents WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?
+        .try_get("stored_name")?;
+
+    sqlx::query("DELETE FROM documents WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    // The file stays while another document has the same content.
+    let shared: i64 = sqlx::query("SELECT COUNT(*) AS count FROM documents WHERE stored_name = ?")
+        .bind(&stored_name)
+        .fetch_one(&mut *tx)
+        .await?
+        .try_get("count")?;
+
+    audit::record(&mut tx, "delete_document", "document", id, Some(audit::snapshot(&before)), None).await?;
+
+    tx.commit().await?;
+
+    if shared == 0 {
+        documents::remove(&state.data_dir, &stored_name)?;
+    }
+    Ok(())
+}
+
+async fn fetch_document(conn: &mut SqliteConnection, id: i64) -> Result<Option<Document>, CommandError> {
+    let document = sqlx::query_as::<_, Document>(
+        "SELECT id, member_id, coop_member_id, kind, file_name, mime_type, size, sha256, note, created_at
+         FROM documents WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(document)
+}
+
+/// Writes a copy of the database and of the document store into a new
+/// `koopasist-yedek-<date>-<time>` folder under `destination`.
+#[tauri::command]
+pub async fn create_backup(state: State<'_, AppState>, destination: String) -> Result<Backup, CommandError> {
+    let folder = format!("koopasist-yedek-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let backup_dir = PathBuf::from(destination).join(folder);
+    std::fs::create_dir_all(&backup_dir)?;
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup_dir.join("emlak.db").to_string_lossy().to_string())
+        .execute(&state.db)
+        .await?;
+    let documents = documents::copy_store(&state.data_dir, &backup_dir.join("documents"))?;
+
+    Ok(Backup { path: backup_dir.to_string_lossy().to_string(), documents })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::{pool::PoolConnection, Pool};
+
+    /// A migrated in-memory database holding member 1 in cooperative 1 as
+    /// membership 1; one connection, so every query sees the same database.
+    async fn memory_db() -> PoolConnection<Sqlite> {
+        let db: Pool<Sqlite> = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::migrations::run(&db, std::path::Path::new(":memory:")).await.unwrap();
+
+        let mut conn = db.acquire().await.unwrap();
+        sqlx::query(
+            "INSERT INTO members (id, tc_number, full_name, phone_1, registration_date) VALUES (1, '10000000146', 'Ali Veli', '+905321112233', '2024-01-01');
+            INSERT INTO cooperatives (id, name, start_date) VALUES (1, 'Koop', '2024-01-01');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date) VALUES (1, 1, 1, '2024-01-01');"
+        )
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+        conn
+    }
+
+    async fn insert_due(conn: &mut SqliteConnection, period: &str, lira: f64) -> i64 {
+        sqlx::query("INSERT INTO dues (coop_member_id, period, amount, status) VALUES (1, ?, ?, 'unpaid')")
+            .bind(period)
+            .bind(Money::from_lira(lira))
+            .execute(&mut *conn)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    async fn due_totals(conn: &mut SqliteConnection, due_id: i64) -> (Money, String) {
+        let due = fetch_due(conn, due_id).await.unwrap().unwrap();
+        (due.paid_amount, due.status)
+    }
+
+    #[tokio::test]
+    async fn overpayment_settles_into_credit_and_pays_open_dues() {
+        let mut conn = memory_db().await;
+        let first = insert_due(&mut conn, "2024-01-01", 100.0).await;
+        let second = insert_due(&mut conn, "2024-02-01", 30.0).await;
+        let third = insert_due(&mut conn, "2024-03-01", 40.0).await;
+
+        insert_payment(&mut conn, "pay_due", first, Money::from_lira(150.0), "2024-01-10", None, None, None).await.unwrap();
+        settle_due(&mut conn, first, "2024-01-10", "pay_due").await.unwrap();
+        assert_eq!(due_totals(&mut conn, first).await, (Money::from_lira(100.0), "paid".to_string()));
+        assert_eq!(credit_balance(&mut conn, 1).await.unwrap(), Money::from_lira(50.0));
+
+        // Oldest first: the second due is paid off, the third gets the rest.
+        apply_member_credit(&mut conn, 1, "2024-01-10", "pay_due").await.unwrap();
+        assert_eq!(due_totals(&mut conn, second).await, (Money::from_lira(30.0), "paid".to_string()));
+        assert_eq!(due_totals(&mut conn, third).await, (Money::from_lira(20.0), "partial".to_string()));
+        assert_eq!(credit_balance(&mut conn, 1).await.unwrap(), Money::ZERO);
+    }
+
+    #[tokio::test]
+    async fn negative_balance_is_covered_from_credit_only_while_it_lasts() {
+        let mut conn = memory_db().await;
+        let due = insert_due(&mut conn, "2024-01-01", 100.0).await;
+        insert_credit_entry(&mut conn, "test", 1, Money::from_lira(30.0), "deposit", "2024-01-01", None, None, None, None).await.unwrap();
+
+        insert_payment(&mut conn, "test", due, Money::from_lira(-20.0), "2024-01-05", None, None, None).await.unwrap();
+        settle_due(&mut conn, due, "2024-01-05", "test").await.unwrap();
+        assert_eq!(due_totals(&mut conn, due).await, (Money::ZERO, "unpaid".to_string()));
+        assert_eq!(credit_balance(&mut conn, 1).await.unwrap(), Money::from_lira(10.0));
+
+        insert_payment(&mut conn, "test", due, Money::from_lira(-20.0), "2024-01-06", None, None, None).await.unwrap();
+        let err = settle_due(&mut conn, due, "2024-01-06", "test").await.unwrap_err();
+        assert!(matches!(err, CommandError::Conflict(ErrorCode::CreditAlreadyUsed)));
+    }
+
+    #[tokio::test]
+    async fn statement_carries_earlier_entries_into_the_opening_balance() {
+        let mut conn = memory_db().await;
+        let january = insert_due(&mut conn, "2024-01-01", 100.0).await;
+        insert_due(&mut conn, "2024-02-01", 100.0).await;
+        insert_payment(&mut conn, "test", january, Money::from_lira(60.0), "2024-01-10", None, None, None).await.unwrap();
+        insert_credit_entry(&mut conn, "test", 1, Money::from_lira(50.0), "deposit", "2024-02-15", None, None, Some("R-7"), None).await.unwrap();
+
+        let statement = member_statement(&mut conn, 1, dates::parse("2024-02-01"), dates::parse("2024-12-31").unwrap()).await.unwrap();
+        assert_eq!(statement.opening_balance, Money::from_lira(40.0));
+        let lines: Vec<_> = statement.lines.iter().map(|l| (l.date.as_str(), l.description.as_str(), l.balance)).collect();
+        assert_eq!(lines, [
+            ("2024-02-01", "2024-02 aidatı", Money::from_lira(140.0)),
+            ("2024-02-15", "Hesaba yatırılan ödeme", Money::from_lira(90.0)),
+        ]);
+        assert_eq!(statement.lines[1].receipt_no.as_deref(), Some("R-7"));
+        assert_eq!(statement.closing_balance, Money::from_lira(90.0));
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{de, Deserialize, Deserializer};
+
+/// Every column holding a date, as `(table, column)`. All are stored as
+/// `YYYY-MM-DD`; `dues.period` is always the first day of its month.
+pub const STORED_COLUMNS: &[(&str, &str)] = &[
+    ("members", "registration_date"),
+    ("cooperatives", "start_date"),
+    ("cooperative_members", "entry_date"),
+    ("cooperative_members", "exit_date"),
+    ("dues", "period"),
+    ("dues", "due_date"),
+    ("dues", "payment_date"),
+    ("payments", "payment_date"),
+    ("member_credits", "entry_date"),
+    ("membership_transfers", "transfer_date"),
+];
+
+// ISO first; the others are how dates are typed in Turkey.
+const FORMATS: [&str; 3] = ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y"];
+
+/// Parses a date given as `2024-02-05`, `05.02.2024` or `05/02/2024`.
+///
+/// Impossible dates such as `31.02.2024` are rejected, as are years without
+/// four digits, which chrono would otherwise read as `05.02.0024`.
+pub fn parse(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .filter(|date| (1000..=9999).contains(&date.year()))
+}
+
+/// First day of the month `date` falls in, the form `dues.period` is stored in.
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// The period of `month` (1-12) in `year`, or `None` if there is no such month.
+pub fn period(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1).filter(|date| (1000..=9999).contains(&date.year()))
+}
+
+/// First day of the month after the one `date` falls in.
+pub fn next_month_start(date: NaiveDate) -> NaiveDate {
+    let start = month_start(date);
+    start.checked_add_months(Months::new(1)).unwrap_or(start)
+}
+
+/// `2024-02`, the month `date` falls in as shown on statements.
+pub fn month_label(date: NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+/// The `YYYY-MM-DD` form of a stored value of `column`, if it is a date at all.
+pub fn repair(column: &str, value: &str) -> Option<String> {
+    let date = parse(value)?;
+    let date = if column == "period" { month_start(date) } else { date };
+    Some(date.to_string())
+}
+
+/// `deserialize_with` helper for date fields of command arguments.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse(&value).ok_or_else(|| {
+        de::Error::custom(format!("invalid date: {} (expected YYYY-MM-DD or DD.MM.YYYY)", value))
+    })
+}
+
+/// Like [`deserialize`], for optional fields; an empty string counts as missing.
+pub fn deserialize_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(value) if value.trim().is_empty() => Ok(None),
+        Some(value) => parse(&value).map(Some).ok_or_else(|| {
+            de::Error::custom(format!("invalid date: {} (expected YYYY-MM-DD or DD.MM.YYYY)", value))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepted_forms() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 5);
+        assert_eq!(parse("2024-02-05"), date);
+        assert_eq!(parse(" 05.02.2024 "), date);
+        assert_eq!(parse("05/02/2024"), date);
+    }
+
+    #[test]
+    fn parse_rejects_impossible_dates_and_short_years() {
+        assert_eq!(parse("31.02.2024"), None);
+        assert_eq!(parse("05.02.24"), None);
+        assert_eq!(parse("2024-13-01"), None);
+        assert_eq!(parse(""), None);
+    }
+}
@@ -0,0 +1,104 @@
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directory under the app data directory holding attached documents.
+/// Each file is stored once, named after its SHA-256 hash, however many
+/// members it is attached to.
+pub fn store_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("documents")
+}
+
+/// Hex SHA-256 and size of a file.
+pub fn hash_file(path: &Path) -> io::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut File::open(path)?, &mut hasher)?;
+    let hash = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    Ok((hash, size))
+}
+
+/// Where a file with this hash is kept, relative to [`store_dir`]. The
+/// extension is kept so the system opens it with the right program.
+pub fn stored_name(sha256: &str, file_name: &str) -> String {
+    match extension(file_name) {
+        Some(ext) => format!("{}/{}.{}", &sha256[..2], sha256, ext),
+        None => format!("{}/{}", &sha256[..2], sha256),
+    }
+}
+
+/// Copies `source` into the store. Returns `false` if it was already there.
+pub fn store(data_dir: &Path, source: &Path, stored_name: &str) -> io::Result<bool> {
+    let target = store_dir(data_dir).join(stored_name);
+    if target.exists() {
+        return Ok(false);
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Copy next to the target first so a half-written file never looks stored.
+    let partial = target.with_extension("part");
+    fs::copy(source, &partial)?;
+    fs::rename(&partial, &target)?;
+    Ok(true)
+}
+
+/// Removes a stored file; one that is already gone is not an error.
+pub fn remove(data_dir: &Path, stored_name: &str) -> io::Result<()> {
+    match fs::remove_file(store_dir(data_dir).join(stored_name)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Copies the whole store into `target`, returning the number of files.
+pub fn copy_store(data_dir: &Path, target: &Path) -> io::Result<i64> {
+    let source = store_dir(data_dir);
+    if !source.exists() {
+        return Ok(0);
+    }
+    copy_dir(&source, target)
+}
+
+fn copy_dir(source: &Path, target: &Path) -> io::Result<i64> {
+    fs::create_dir_all(target)?;
+    let mut count = 0;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            count += copy_dir(&path, &target.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, target.join(entry.file_name()))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn extension(file_name: &str) -> Option<String> {
+    Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(|ext| ext.to_ascii_lowercase())
+}
+
+/// Media type of a file, guessed from its extension.
+pub fn mime_type(file_name: &str) -> &'static str {
+    match extension(file_name).as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("tif") | Some("tiff") => "image/tiff",
+        Some("heic") => "image/heic",
+        Some("doc") => "application/msword",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("odt") => "application/vnd.oasis.opendocument.text",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,304 @@
+use crate::money::Money;
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+
+/// A problem with one input field, for the form to show next to it.
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+    pub message_tr: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: &str, message_tr: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.to_string(),
+            message_tr: message_tr.to_string(),
+        }
+    }
+}
+
+/// The record a `NotFound` error is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    Member,
+    Cooperative,
+    CoopMember,
+    Due,
+    Payment,
+    DuesPlan,
+    PenaltyRule,
+    Document,
+}
+
+impl Entity {
+    fn code(self) -> &'static str {
+        match self {
+            Entity::Member => "member_not_found",
+            Entity::Cooperative => "cooperative_not_found",
+            Entity::CoopMember => "coop_member_not_found",
+            Entity::Due => "due_not_found",
+            Entity::Payment => "payment_not_found",
+            Entity::DuesPlan => "dues_plan_not_found",
+            Entity::PenaltyRule => "penalty_rule_not_found",
+            Entity::Document => "document_not_found",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Entity::Member => "Member not found",
+            Entity::Cooperative => "Cooperative not found",
+            Entity::CoopMember => "Member not found in cooperative",
+            Entity::Due => "Due not found",
+            Entity::Payment => "Payment not found",
+            Entity::DuesPlan => "Dues plan not found",
+            Entity::PenaltyRule => "No penalty rule defined for this cooperative",
+            Entity::Document => "Document not found",
+        }
+    }
+
+    fn message_tr(self) -> &'static str {
+        match self {
+            Entity::Member => "Üye bulunamadı.",
+            Entity::Cooperative => "Kooperatif bulunamadı.",
+            Entity::CoopMember => "Üye bu kooperatifte bulunamadı.",
+            Entity::Due => "Aidat bulunamadı.",
+            Entity::Payment => "Ödeme bulunamadı.",
+            Entity::DuesPlan => "Aidat planı bulunamadı.",
+            Entity::PenaltyRule => "Bu kooperatif için gecikme zammı kuralı tanımlanmamış.",
+            Entity::Document => "Belge bulunamadı.",
+        }
+    }
+}
+
+/// Reasons a request is rejected, each with a stable code the frontend can
+/// match on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorCode {
+    // Invalid input
+    InvalidDate,
+    InvalidDateRange,
+    InvalidStoredDate,
+    AmountNotPositive,
+    ReasonRequired,
+    InvalidPenaltyRule,
+    RefundRateOutOfRange,
+    StartMonthOutOfRange,
+    InvalidExtraDueKind,
+    DateBeforeEntry,
+    SameMemberTransfer,
+    ReversalOfReversal,
+    NotACollection,
+    DocumentOwner,
+    // Conflicts with stored data
+    Duplicate,
+    TcNumberTaken,
+    PeriodExists,
+    AlreadyReversed,
+    PaymentOfDeletedDue,
+    DueNotDeleted,
+    CreditAlreadyUsed,
+    AlreadyLeft,
+    MemberLeft { exit_date: String },
+    AlreadyMember,
+    DueHasPayments { period: String },
+    RefundExceedsCredit { balance: Money },
+    CoopInUse { members: i64, dues: i64 },
+    DocumentExists,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidDate => "invalid_date",
+            ErrorCode::InvalidDateRange => "invalid_date_range",
+            ErrorCode::InvalidStoredDate => "invalid_stored_date",
+            ErrorCode::AmountNotPositive => "amount_not_positive",
+            ErrorCode::ReasonRequired => "reason_required",
+            ErrorCode::InvalidPenaltyRule => "invalid_penalty_rule",
+            ErrorCode::RefundRateOutOfRange => "refund_rate_out_of_range",
+            ErrorCode::StartMonthOutOfRange => "start_month_out_of_range",
+            ErrorCode::InvalidExtraDueKind => "invalid_extra_due_kind",
+            ErrorCode::DateBeforeEntry => "date_before_entry",
+            ErrorCode::SameMemberTransfer => "same_member_transfer",
+            ErrorCode::ReversalOfReversal => "reversal_of_reversal",
+            ErrorCode::NotACollection => "not_a_collection",
+            ErrorCode::DocumentOwner => "document_owner",
+            ErrorCode::Duplicate => "duplicate",
+            ErrorCode::TcNumberTaken => "tc_taken",
+            ErrorCode::PeriodExists => "period_exists",
+            ErrorCode::AlreadyReversed => "already_reversed",
+            ErrorCode::PaymentOfDeletedDue => "payment_of_deleted_due",
+            ErrorCode::DueNotDeleted => "due_not_deleted",
+            ErrorCode::CreditAlreadyUsed => "credit_already_used",
+            ErrorCode::AlreadyLeft => "already_left",
+            ErrorCode::MemberLeft { .. } => "member_left",
+            ErrorCode::AlreadyMember => "already_member",
+            ErrorCode::DueHasPayments { .. } => "due_has_payments",
+            ErrorCode::RefundExceedsCredit { .. } => "refund_exceeds_credit",
+            ErrorCode::CoopInUse { .. } => "coop_in_use",
+            ErrorCode::DocumentExists => "document_exists",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ErrorCode::InvalidDate => "Invalid date format".to_string(),
+            ErrorCode::InvalidDateRange => "Start date is after the end date".to_string(),
+            ErrorCode::InvalidStoredDate => "A date stored in the database is invalid".to_string(),
+            ErrorCode::AmountNotPositive => "Amount must be positive".to_string(),
+            ErrorCode::ReasonRequired => "A reason is required to reverse a payment".to_string(),
+            ErrorCode::InvalidPenaltyRule => "Invalid penalty rule".to_string(),
+            ErrorCode::RefundRateOutOfRange => "Exit refund rate must be between 0 and 100".to_string(),
+            ErrorCode::StartMonthOutOfRange => "Start month must be between 1 and 12".to_string(),
+            ErrorCode::InvalidExtraDueKind => "Monthly and penalty dues cannot be added as extra dues".to_string(),
+            ErrorCode::DateBeforeEntry => "Date cannot be before the entry date".to_string(),
+            ErrorCode::SameMemberTransfer => "A membership cannot be transferred to the same member".to_string(),
+            ErrorCode::ReversalOfReversal => "A reversal entry cannot itself be reversed".to_string(),
+            ErrorCode::NotACollection => "Only collections can be reversed".to_string(),
+            ErrorCode::DocumentOwner => "A document belongs to either a member or a membership".to_string(),
+            ErrorCode::Duplicate => "A record with the same values already exists".to_string(),
+            ErrorCode::TcNumberTaken => "Another member is registered with this TC Kimlik No".to_string(),
+            ErrorCode::PeriodExists => "A due already exists for this period".to_string(),
+            ErrorCode::AlreadyReversed => "Payment has already been reversed".to_string(),
+            ErrorCode::PaymentOfDeletedDue => "Payment belongs to a deleted due; restore the due first".to_string(),
+            ErrorCode::DueNotDeleted => "Due is not deleted".to_string(),
+            ErrorCode::CreditAlreadyUsed => "The credit created by this payment has already been used; reverse the payments it settled first".to_string(),
+            ErrorCode::AlreadyLeft => "Member has already left the cooperative".to_string(),
+            ErrorCode::MemberLeft { exit_date } => format!("Member left the cooperative on {}", exit_date),
+            ErrorCode::AlreadyMember => "The new member already belongs to this cooperative".to_string(),
+            ErrorCode::DueHasPayments { period } => format!("Due for {} has payments recorded; delete it with force to move them to the member's credit", period),
+            ErrorCode::RefundExceedsCredit { balance } => format!("Refund exceeds the member's credit balance ({})", balance),
+            ErrorCode::CoopInUse { members, dues } => format!("Cooperative has {} members and {} dues; archive it instead", members, dues),
+            ErrorCode::DocumentExists => "This file is already attached".to_string(),
+        }
+    }
+
+    pub fn message_tr(&self) -> String {
+        match self {
+            ErrorCode::InvalidDate => "Geçersiz tarih biçimi.".to_string(),
+            ErrorCode::InvalidDateRange => "Başlangıç tarihi bitiş tarihinden sonra olamaz.".to_string(),
+            ErrorCode::InvalidStoredDate => "Veritabanında kayıtlı bir tarih geçersiz.".to_string(),
+            ErrorCode::AmountNotPositive => "Tutar sıfırdan büyük olmalıdır.".to_string(),
+            ErrorCode::ReasonRequired => "Ödemeyi iptal etmek için bir gerekçe girilmelidir.".to_string(),
+            ErrorCode::InvalidPenaltyRule => "Geçersiz gecikme zammı kuralı.".to_string(),
+            ErrorCode::RefundRateOutOfRange => "Çıkış iade oranı 0 ile 100 arasında olmalıdır.".to_string(),
+            ErrorCode::StartMonthOutOfRange => "Başlangıç ayı 1 ile 12 arasında olmalıdır.".to_string(),
+            ErrorCode::InvalidExtraDueKind => "Aylık aidat ve gecikme zammı ek ödeme olarak eklenemez.".to_string(),
+            ErrorCode::DateBeforeEntry => "Tarih, üyenin giriş tarihinden önce olamaz.".to_string(),
+            ErrorCode::SameMemberTransfer => "Üyelik aynı kişiye devredilemez.".to_string(),
+            ErrorCode::ReversalOfReversal => "İptal kaydı tekrar iptal edilemez.".to_string(),
+            ErrorCode::NotACollection => "Yalnızca tahsilatlar iptal edilebilir.".to_string(),
+            ErrorCode::DocumentOwner => "Belge ya bir üyeye ya da bir kooperatif üyeliğine eklenmelidir.".to_string(),
+            ErrorCode::Duplicate => "Aynı bilgilere sahip bir kayıt zaten mevcut.".to_string(),
+            ErrorCode::TcNumberTaken => "Bu TC Kimlik No ile kayıtlı başka bir üye var.".to_string(),
+            ErrorCode::PeriodExists => "Bu dönem için aidat zaten mevcut.".to_string(),
+            ErrorCode::AlreadyReversed => "Bu ödeme zaten iptal edilmiş.".to_string(),
+            ErrorCode::PaymentOfDeletedDue => "Ödeme silinmiş bir aidata ait; önce aidatı geri yükleyin.".to_string(),
+            ErrorCode::DueNotDeleted => "Aidat silinmemiş.".to_string(),
+            ErrorCode::CreditAlreadyUsed => "Bu ödemenin oluşturduğu alacak kullanılmış; önce mahsup edilen ödemeleri iptal edin.".to_string(),
+            ErrorCode::AlreadyLeft => "Üye kooperatiften zaten ayrılmış.".to_string(),
+            ErrorCode::MemberLeft { exit_date } => format!("Üye {} tarihinde kooperatiften ayrıldı.", exit_date),
+            ErrorCode::AlreadyMember => "Yeni üye zaten bu kooperatifin üyesi.".to_string(),
+            ErrorCode::DueHasPayments { period } => format!("{} dönemi aidatına ödeme yapılmış; ödenen tutarı üye alacağına aktararak silmek için zorla silin.", period),
+            ErrorCode::RefundExceedsCredit { balance } => format!("İade tutarı üyenin alacak bakiyesini ({} TL) aşıyor.", balance),
+            ErrorCode::CoopInUse { members, dues } => format!("Kooperatifin {} üyesi ve {} aidatı var; silmek yerine arşivleyin.", members, dues),
+            ErrorCode::DocumentExists => "Bu dosya zaten eklenmiş.".to_string(),
+        }
+    }
+}
+
+/// Error returned by every command. It reaches the frontend as
+/// `{ kind, code, message, message_tr }`, plus `fields` for validation errors.
+#[derive(Debug)]
+pub enum CommandError {
+    NotFound(Entity),
+    Validation(Vec<FieldError>),
+    Invalid(ErrorCode),
+    Conflict(ErrorCode),
+    Database(String),
+    File(String),
+}
+
+impl CommandError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CommandError::NotFound(_) => "not_found",
+            CommandError::Validation(_) => "validation",
+            CommandError::Invalid(_) => "invalid",
+            CommandError::Conflict(_) => "conflict",
+            CommandError::Database(_) => "database",
+            CommandError::File(_) => "file",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::NotFound(entity) => entity.code(),
+            CommandError::Validation(_) => "validation",
+            CommandError::Invalid(code) | CommandError::Conflict(code) => code.as_str(),
+            CommandError::Database(_) => "database",
+            CommandError::File(_) => "file",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            CommandError::NotFound(entity) => entity.message().to_string(),
+            CommandError::Validation(_) => "Some fields are invalid".to_string(),
+            CommandError::Invalid(code) | CommandError::Conflict(code) => code.message(),
+            CommandError::Database(message) => format!("Database error: {}", message),
+            CommandError::File(message) => format!("File error: {}", message),
+        }
+    }
+
+    pub fn message_tr(&self) -> String {
+        match self {
+            CommandError::NotFound(entity) => entity.message_tr().to_string(),
+            CommandError::Validation(_) => "Bazı alanlar hatalı.".to_string(),
+            CommandError::Invalid(code) | CommandError::Conflict(code) => code.message_tr(),
+            CommandError::Database(message) => format!("Veritabanı hatası: {}", message),
+            CommandError::File(message) => format!("Dosya hatası: {}", message),
+        }
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 5)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("message_tr", &self.message_tr())?;
+        if let CommandError::Validation(fields) = self {
+            state.serialize_field("fields", fields)?;
+        }
+        state.end()
+    }
+}
+
+impl From<sqlx::Error> for CommandError {
+    fn from(e: sqlx::Error) -> Self {
+        if let Some(db) = e.as_database_error() {
+            if db.is_unique_violation() {
+                return if db.message().contains("members.tc_number") {
+                    CommandError::Conflict(ErrorCode::TcNumberTaken)
+                } else {
+                    CommandError::Conflict(ErrorCode::Duplicate)
+                };
+            }
+        }
+        CommandError::Database(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        CommandError::File(e.to_string())
+    }
+}
@@ -5,8 +5,18 @@ fn greet(name: &str) -> String {
 }
 
 use tauri::Manager; // Fix: Import Manager trait
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+mod audit;
+mod dates;
 mod db;
+mod documents;
+mod error;
+mod migrations;
 mod models;
+mod money;
+mod penalties;
+mod search;
+mod validation;
 mod commands;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -16,10 +26,20 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .setup(|app| {
-            tauri::async_runtime::block_on(async {
-                let state = db::init_db(app.handle()).await.expect("failed to init db");
-                app.manage(state);
-            });
+            // Refuse to start rather than run against a database we could not migrate.
+            let state = match tauri::async_runtime::block_on(db::init_db(app.handle())) {
+                Ok(state) => state,
+                Err(e) => {
+                    // Release builds have no console, so say why before giving up.
+                    app.dialog()
+                        .message(format!("Veritabanı hazırlanamadığı için uygulama başlatılamıyor.\n\n{}", e))
+                        .kind(MessageDialogKind::Error)
+                        .title("GempaSoft-KoopAsist")
+                        .blocking_show();
+                    return Err(e.into());
+                }
+            };
+            app.manage(state);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -27,23 +47,57 @@ pub fn run() {
             commands::create_member,
             commands::get_members,
             commands::update_member,
+            commands::find_invalid_tc_numbers,
+            commands::find_invalid_phones,
+            commands::find_invalid_dates,
             commands::search_members,
+            commands::list_members,
             commands::create_coop,
             commands::get_coops,
+            commands::update_coop,
+            commands::archive_coop,
+            commands::delete_coop,
             commands::get_coop_details,
             commands::add_members_to_coop,
             commands::get_coop_members,
+            commands::leave_coop,
+            commands::get_exit_settlement,
+            commands::set_exit_refund_rate,
+            commands::transfer_membership,
+            commands::get_ownership_chain,
             commands::get_available_members,
             commands::generate_dues,
             commands::add_next_due,
+            commands::save_dues_plan,
+            commands::get_dues_plans,
+            commands::delete_dues_plan,
+            commands::apply_dues_plan,
             commands::get_member_dues,
+            commands::get_member_statement,
             commands::pay_due,
+            commands::pay_member_balance,
+            commands::get_due_payments,
+            commands::reverse_payment,
+            commands::get_member_credit,
+            commands::refund_member_credit,
             commands::generate_yearly_dues,
             commands::delete_due,
             commands::delete_yearly_dues,
+            commands::get_deleted_dues,
+            commands::restore_due,
             commands::update_due_amount,
             commands::add_extra_due,
-            commands::get_payment_receipt_info
+            commands::get_due_totals_by_kind,
+            commands::get_payment_receipt_info,
+            commands::get_penalty_rule,
+            commands::set_penalty_rule,
+            commands::calculate_penalties,
+            commands::get_audit_log,
+            commands::attach_document,
+            commands::get_documents,
+            commands::open_document,
+            commands::delete_document,
+            commands::create_backup
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
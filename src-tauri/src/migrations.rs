@@ -0,0 +1,948 @@
+use crate::dates;
+use crate::search;
+use crate::validation;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::path::Path;
+
+/// A single schema upgrade step. Steps are applied in `version` order, each one
+/// inside its own transaction together with its `schema_version` row, so a
+/// failing step leaves the database exactly at the previous version.
+///
+/// Released steps must never be edited; add a new one instead.
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+    /// Rewrites existing rows after `sql`, in the same transaction.
+    pub data: Option<DataStep>,
+}
+
+/// Data changes that need Rust code rather than SQL.
+#[derive(Debug, Clone, Copy)]
+pub enum DataStep {
+    /// Brings stored phone numbers to E.164. Numbers that cannot be parsed are
+    /// kept as they are and reported by `find_invalid_phones`.
+    NormalizePhones,
+    /// Rewrites stored dates such as `05.02.2024` to `YYYY-MM-DD`. Values that
+    /// are not dates are kept and reported by `find_invalid_dates`.
+    RepairDates,
+    /// Fills `member_search` from `members`.
+    IndexMembers,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        sql: "CREATE TABLE IF NOT EXISTS members (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tc_number TEXT NOT NULL UNIQUE,
+            full_name TEXT NOT NULL,
+            phone_1 TEXT NOT NULL,
+            phone_2 TEXT,
+            registration_date TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE IF NOT EXISTS cooperatives (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            start_date TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE IF NOT EXISTS cooperative_members (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            coop_id INTEGER NOT NULL,
+            member_id INTEGER NOT NULL,
+            entry_date TEXT NOT NULL,
+            FOREIGN KEY(coop_id) REFERENCES cooperatives(id),
+            FOREIGN KEY(member_id) REFERENCES members(id)
+        );
+        CREATE TABLE IF NOT EXISTS dues (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            coop_member_id INTEGER NOT NULL,
+            period TEXT NOT NULL,
+            amount REAL NOT NULL,
+            paid_amount REAL DEFAULT 0,
+            status TEXT DEFAULT 'unpaid',
+            payment_date TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(coop_member_id) REFERENCES cooperative_members(id)
+        );",
+        data: None,
+    },
+    Migration {
+        version: 2,
+        description: "store due amounts as integer kurus",
+        sql: "CREATE TABLE dues_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            coop_member_id INTEGER NOT NULL,
+            period TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            paid_amount INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL DEFAULT 'unpaid',
+            payment_date TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(coop_member_id) REFERENCES cooperative_members(id)
+        );
+        INSERT INTO dues_new (id, coop_member_id, period, amount, paid_amount, status, payment_date, created_at)
+        SELECT id, coop_member_id, period,
+               CAST(ROUND(amount * 100) AS INTEGER),
+               CAST(ROUND(COALESCE(paid_amount, 0) * 100) AS INTEGER),
+               COALESCE(status, 'unpaid'), payment_date, created_at
+        FROM dues;
+        DROP TABLE dues;
+        ALTER TABLE dues_new RENAME TO dues;
+        -- Statuses were decided on fractional amounts; settle them on the exact ones.
+        UPDATE dues SET status = CASE
+            WHEN paid_amount > 0 AND paid_amount >= amount THEN 'paid'
+            WHEN paid_amount > 0 THEN 'partial'
+            ELSE 'unpaid'
+        END;",
+        data: None,
+    },
+    Migration {
+        version: 3,
+        description: "payment ledger",
+        sql: "CREATE TABLE payments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            due_id INTEGER NOT NULL,
+            amount INTEGER NOT NULL,
+            payment_date TEXT NOT NULL,
+            method TEXT NOT NULL DEFAULT 'cash',
+            receipt_no TEXT,
+            created_by TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(due_id) REFERENCES dues(id)
+        );
+        CREATE INDEX idx_payments_due_id ON payments(due_id);
+        -- Earlier collections only survive as a running total; keep each as a single entry.
+        INSERT INTO payments (due_id, amount, payment_date, method)
+        SELECT id, paid_amount, COALESCE(payment_date, substr(created_at, 1, 10), period), 'legacy'
+        FROM dues
+        WHERE paid_amount <> 0;",
+        data: None,
+    },
+    Migration {
+        version: 4,
+        description: "payment reversals",
+        sql: "ALTER TABLE payments ADD COLUMN reversal_of INTEGER REFERENCES payments(id);
+        ALTER TABLE payments ADD COLUMN reason TEXT;
+        CREATE UNIQUE INDEX idx_payments_reversal_of ON payments(reversal_of) WHERE reversal_of IS NOT NULL;",
+        data: None,
+    },
+    Migration {
+        version: 5,
+        description: "member credit balance",
+        sql: "CREATE TABLE member_credits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            coop_member_id INTEGER NOT NULL,
+            amount INTEGER NOT NULL,
+            entry_type TEXT NOT NULL,
+            entry_date TEXT NOT NULL,
+            payment_id INTEGER,
+            note TEXT,
+            created_by TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(coop_member_id) REFERENCES cooperative_members(id),
+            FOREIGN KEY(payment_id) REFERENCES payments(id)
+        );
+        CREATE INDEX idx_member_credits_coop_member_id ON member_credits(coop_member_id);
+        -- Move what was paid beyond each due's amount into the member's credit.
+        INSERT INTO payments (due_id, amount, payment_date, method, reason)
+        SELECT d.id, d.amount - SUM(p.amount), COALESCE(d.payment_date, d.period), 'credit',
+               'Fazla ödeme üye alacağına aktarıldı'
+        FROM dues d
+        JOIN payments p ON p.due_id = d.id
+        GROUP BY d.id
+        HAVING SUM(p.amount) > d.amount;
+        INSERT INTO member_credits (coop_member_id, amount, entry_type, entry_date, payment_id, note)
+        SELECT d.coop_member_id, -p.amount, 'overpayment', p.payment_date, p.id, p.reason
+        FROM payments p
+        JOIN dues d ON d.id = p.due_id
+        WHERE p.method = 'credit' AND p.amount < 0;
+        UPDATE dues SET paid_amount = amount, status = 'paid' WHERE paid_amount > amount;",
+        data: None,
+    },
+    Migration {
+        version: 6,
+        description: "late payment penalties",
+        sql: "CREATE TABLE penalty_rules (
+            coop_id INTEGER PRIMARY KEY,
+            monthly_rate REAL NOT NULL,
+            compound INTEGER NOT NULL DEFAULT 0,
+            grace_days INTEGER NOT NULL DEFAULT 0,
+            cap_rate REAL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(coop_id) REFERENCES cooperatives(id)
+        );
+        -- Existing dues fall due on their period date.
+        ALTER TABLE dues ADD COLUMN due_date TEXT;
+        UPDATE dues SET due_date = period;
+        ALTER TABLE dues ADD COLUMN penalty_of INTEGER REFERENCES dues(id);
+        CREATE INDEX idx_dues_penalty_of ON dues(penalty_of);",
+        data: None,
+    },
+    Migration {
+        version: 7,
+        description: "due kinds and descriptions",
+        sql: "ALTER TABLE dues ADD COLUMN kind TEXT NOT NULL DEFAULT 'monthly'
+            CHECK (kind IN ('monthly', 'extra', 'share_capital', 'penalty', 'other'));
+        ALTER TABLE dues ADD COLUMN description TEXT;
+        UPDATE dues SET kind = 'penalty' WHERE penalty_of IS NOT NULL;
+        -- Before kinds existed the first due of a period was the monthly one and the rest were extras.
+        UPDATE dues SET kind = 'extra'
+        WHERE penalty_of IS NULL
+          AND id NOT IN (SELECT MIN(id) FROM dues WHERE penalty_of IS NULL GROUP BY coop_member_id, period);
+        CREATE UNIQUE INDEX idx_dues_monthly_period ON dues(coop_member_id, period) WHERE kind = 'monthly';",
+        data: None,
+    },
+    Migration {
+        version: 8,
+        description: "audit log",
+        sql: "CREATE TABLE audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            command TEXT NOT NULL,
+            entity TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            before_json TEXT,
+            after_json TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX idx_audit_log_entity ON audit_log(entity, entity_id);
+        CREATE INDEX idx_audit_log_created_at ON audit_log(created_at);
+        CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+        BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+        END;
+        CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+        BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+        END;",
+        data: None,
+    },
+    Migration {
+        version: 9,
+        description: "soft delete for dues",
+        sql: "ALTER TABLE dues ADD COLUMN deleted_at DATETIME;
+        ALTER TABLE dues ADD COLUMN deleted_reason TEXT;
+        DROP INDEX idx_dues_monthly_period;
+        CREATE UNIQUE INDEX idx_dues_monthly_period ON dues(coop_member_id, period)
+            WHERE kind = 'monthly' AND deleted_at IS NULL;",
+        data: None,
+    },
+    Migration {
+        version: 10,
+        description: "member exit",
+        sql: "ALTER TABLE cooperative_members ADD COLUMN exit_date TEXT;
+        ALTER TABLE cooperative_members ADD COLUMN exit_reason TEXT
+            CHECK (exit_reason IN ('resigned', 'expelled', 'deceased', 'other'));
+        ALTER TABLE cooperative_members ADD COLUMN exit_note TEXT;
+        ALTER TABLE cooperatives ADD COLUMN exit_refund_rate REAL NOT NULL DEFAULT 0;",
+        data: None,
+    },
+    Migration {
+        version: 11,
+        description: "membership transfers",
+        sql: "CREATE TABLE membership_transfers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            coop_id INTEGER NOT NULL,
+            from_coop_member_id INTEGER NOT NULL UNIQUE,
+            to_coop_member_id INTEGER NOT NULL UNIQUE,
+            transfer_date TEXT NOT NULL,
+            note TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(coop_id) REFERENCES cooperatives(id),
+            FOREIGN KEY(from_coop_member_id) REFERENCES cooperative_members(id),
+            FOREIGN KEY(to_coop_member_id) REFERENCES cooperative_members(id)
+        );",
+        data: None,
+    },
+    Migration {
+        version: 12,
+        description: "cooperative status",
+        sql: "ALTER TABLE cooperatives ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+            CHECK (status IN ('active', 'completed', 'liquidated'));",
+        data: None,
+    },
+    Migration {
+        version: 13,
+        description: "dues plans",
+        sql: "CREATE TABLE dues_plans (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            coop_id INTEGER NOT NULL,
+            year INTEGER NOT NULL,
+            amount INTEGER NOT NULL,
+            amount_type TEXT NOT NULL CHECK (amount_type IN ('monthly', 'yearly')),
+            start_month INTEGER NOT NULL DEFAULT 1 CHECK (start_month BETWEEN 1 AND 12),
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(coop_id, year),
+            FOREIGN KEY(coop_id) REFERENCES cooperatives(id)
+        );",
+        data: None,
+    },
+    Migration {
+        version: 14,
+        description: "normalize phone numbers",
+        sql: "",
+        data: Some(DataStep::NormalizePhones),
+    },
+    Migration {
+        version: 15,
+        description: "repair stored dates",
+        sql: "",
+        data: Some(DataStep::RepairDates),
+    },
+    Migration {
+        version: 16,
+        description: "one active membership per member and cooperative",
+        sql: "CREATE TEMP TABLE membership_merge AS
+        SELECT cm.id AS duplicate_id, k.keep_id
+        FROM cooperative_members cm
+        JOIN (SELECT coop_id, member_id, MIN(id) AS keep_id
+              FROM cooperative_members
+              WHERE exit_date IS NULL
+              GROUP BY coop_id, member_id
+              HAVING COUNT(*) > 1) k ON k.coop_id = cm.coop_id AND k.member_id = cm.member_id
+        WHERE cm.exit_date IS NULL AND cm.id != k.keep_id;
+        -- The kept membership starts with the earliest of the merged ones.
+        UPDATE cooperative_members SET entry_date = (
+            SELECT MIN(o.entry_date) FROM cooperative_members o
+            WHERE o.coop_id = cooperative_members.coop_id AND o.member_id = cooperative_members.member_id AND o.exit_date IS NULL
+        )
+        WHERE id IN (SELECT keep_id FROM membership_merge);
+        -- A second monthly due for the same period is kept, as an extra due.
+        UPDATE dues SET kind = 'extra', description = COALESCE(description, 'Mükerrer üyelikten aktarılan aidat')
+        WHERE kind = 'monthly' AND deleted_at IS NULL
+          AND coop_member_id IN (SELECT duplicate_id FROM membership_merge)
+          AND EXISTS (
+            SELECT 1 FROM dues o
+            JOIN membership_merge mine ON mine.duplicate_id = dues.coop_member_id
+            WHERE o.kind = 'monthly' AND o.deleted_at IS NULL AND o.period = dues.period
+              AND (o.coop_member_id = mine.keep_id
+                   OR (o.id < dues.id AND o.coop_member_id IN (SELECT duplicate_id FROM membership_merge WHERE keep_id = mine.keep_id)))
+          );
+        UPDATE dues SET coop_member_id = (SELECT keep_id FROM membership_merge WHERE duplicate_id = dues.coop_member_id)
+        WHERE coop_member_id IN (SELECT duplicate_id FROM membership_merge);
+        UPDATE member_credits SET coop_member_id = (SELECT keep_id FROM membership_merge WHERE duplicate_id = member_credits.coop_member_id)
+        WHERE coop_member_id IN (SELECT duplicate_id FROM membership_merge);
+        UPDATE membership_transfers SET to_coop_member_id = (SELECT keep_id FROM membership_merge WHERE duplicate_id = membership_transfers.to_coop_member_id)
+        WHERE to_coop_member_id IN (SELECT duplicate_id FROM membership_merge);
+        DELETE FROM cooperative_members WHERE id IN (SELECT duplicate_id FROM membership_merge);
+        DROP TABLE membership_merge;
+        CREATE UNIQUE INDEX idx_cooperative_members_active ON cooperative_members(coop_id, member_id)
+            WHERE exit_date IS NULL;",
+        data: None,
+    },
+    Migration {
+        version: 17,
+        description: "member search index",
+        // Text is folded in Rust (see search::fold) before it is indexed.
+        sql: "CREATE VIRTUAL TABLE member_search USING fts5(full_name, tc_number, phones, tokenize = 'unicode61');",
+        data: Some(DataStep::IndexMembers),
+    },
+    Migration {
+        version: 18,
+        description: "extended member profile",
+        sql: "ALTER TABLE members ADD COLUMN province TEXT;
+        ALTER TABLE members ADD COLUMN district TEXT;
+        ALTER TABLE members ADD COLUMN neighbourhood TEXT;
+        ALTER TABLE members ADD COLUMN address TEXT;
+        ALTER TABLE members ADD COLUMN email TEXT;
+        ALTER TABLE members ADD COLUMN iban TEXT;
+        ALTER TABLE members ADD COLUMN birth_date TEXT;
+        ALTER TABLE members ADD COLUMN occupation TEXT;
+        ALTER TABLE members ADD COLUMN notes TEXT;
+        DROP TABLE member_search;
+        CREATE VIRTUAL TABLE member_search USING fts5(full_name, tc_number, phones, notes, tokenize = 'unicode61');",
+        data: Some(DataStep::IndexMembers),
+    },
+    Migration {
+        version: 19,
+        description: "member documents",
+        sql: "CREATE TABLE documents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            member_id INTEGER REFERENCES members(id),
+            coop_member_id INTEGER REFERENCES cooperative_members(id),
+            kind TEXT NOT NULL DEFAULT 'other'
+                CHECK (kind IN ('id_card', 'application', 'contract', 'other')),
+            file_name TEXT NOT NULL,
+            stored_name TEXT NOT NULL,
+            mime_type TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            sha256 TEXT NOT NULL,
+            note TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            CHECK ((member_id IS NULL) != (coop_member_id IS NULL))
+        );
+        CREATE INDEX idx_documents_sha256 ON documents(sha256);
+        CREATE UNIQUE INDEX idx_documents_member ON documents(member_id, sha256) WHERE member_id IS NOT NULL;
+        CREATE UNIQUE INDEX idx_documents_coop_member ON documents(coop_member_id, sha256) WHERE coop_member_id IS NOT NULL;",
+        data: None,
+    },
+    Migration {
+        version: 20,
+        description: "keep transferred memberships when merging duplicates",
+        // A membership can be the target of one transfer only, so one that came
+        // from a transfer is preferred as the keeper and is never merged away.
+        sql: "CREATE TEMP TABLE membership_merge AS
+        WITH active AS (
+            SELECT cm.id, cm.coop_id, cm.member_id,
+                   EXISTS (SELECT 1 FROM membership_transfers t WHERE t.to_coop_member_id = cm.id) AS transferred
+            FROM cooperative_members cm
+            WHERE cm.exit_date IS NULL
+        ), ranked AS (
+            SELECT id, coop_id, member_id, transferred,
+                   ROW_NUMBER() OVER (PARTITION BY coop_id, member_id ORDER BY transferred DESC, id) AS position
+            FROM active
+        )
+        SELECT d.id AS duplicate_id, k.id AS keep_id, d.transferred
+        FROM ranked d
+        JOIN ranked k ON k.coop_id = d.coop_id AND k.member_id = d.member_id AND k.position = 1
+        WHERE d.position > 1;
+        -- The kept membership starts with the earliest of the merged ones.
+        UPDATE cooperative_members SET entry_date = (
+            SELECT MIN(o.entry_date) FROM cooperative_members o
+            WHERE o.coop_id = cooperative_members.coop_id AND o.member_id = cooperative_members.member_id AND o.exit_date IS NULL
+        )
+        WHERE id IN (SELECT keep_id FROM membership_merge);
+        -- A second monthly due for the same period is kept, as an extra due.
+        UPDATE dues SET kind = 'extra', description = COALESCE(description, 'Mükerrer üyelikten aktarılan aidat')
+        WHERE kind = 'monthly' AND deleted_at IS NULL
+          AND coop_member_id IN (SELECT duplicate_id FROM membership_merge)
+          AND EXISTS (
+            SELECT 1 FROM dues o
+            JOIN membership_merge mine ON mine.duplicate_id = dues.coop_member_id
+            WHERE o.kind = 'monthly' AND o.deleted_at IS NULL AND o.period = dues.period
+              AND (o.coop_member_id = mine.keep_id
+                   OR (o.id < dues.id AND o.coop_member_id IN (SELECT duplicate_id FROM membership_merge WHERE keep_id = mine.keep_id)))
+          );
+        UPDATE dues SET coop_member_id = (SELECT keep_id FROM membership_merge WHERE duplicate_id = dues.coop_member_id)
+        WHERE coop_member_id IN (SELECT duplicate_id FROM membership_merge);
+        UPDATE member_credits SET coop_member_id = (SELECT keep_id FROM membership_merge WHERE duplicate_id = member_credits.coop_member_id)
+        WHERE coop_member_id IN (SELECT duplicate_id FROM membership_merge);
+        -- The keeper has a transfer of its own; such a duplicate stays on record, closed.
+        UPDATE cooperative_members SET exit_date = entry_date, exit_reason = 'other',
+            exit_note = 'Mükerrer üyelik; aidat ve alacaklar aktif üyeliğe aktarıldı'
+        WHERE id IN (SELECT duplicate_id FROM membership_merge WHERE transferred);
+        DELETE FROM cooperative_members WHERE id IN (SELECT duplicate_id FROM membership_merge WHERE NOT transferred);
+        DROP TABLE membership_merge;",
+        data: None,
+    },
+    Migration {
+        version: 21,
+        description: "receipt numbers of credit entries",
+        // Deposits kept their receipt number as the note until now.
+        sql: "ALTER TABLE member_credits ADD COLUMN receipt_no TEXT;
+        UPDATE member_credits SET receipt_no = note, note = NULL WHERE entry_type = 'deposit';",
+        data: None,
+    },
+];
+
+/// Later steps that also run just before an earlier released one, in its
+/// transaction, because that step fails on data the later one cleans up.
+const RUN_BEFORE: &[(i64, i64)] = &[
+    // 16 moves transfers onto the kept membership, which breaks once two of
+    // the merged memberships are transfer targets.
+    (16, 20),
+];
+
+/// Columns of the schema that was in use before `schema_version` existed.
+///
+/// Such databases are adopted as version 1 after being checked against this
+/// list: a column with a definition is added in place when it is missing, a
+/// column without one cannot be reconstructed and aborts the startup.
+const BASELINE_COLUMNS: &[(&str, &str, Option<&str>)] = &[
+    ("members", "tc_number", None),
+    ("members", "full_name", None),
+    ("members", "phone_1", None),
+    ("members", "phone_2", Some("TEXT")),
+    ("members", "registration_date", None),
+    ("members", "created_at", Some("DATETIME")),
+    ("cooperatives", "name", None),
+    ("cooperatives", "start_date", None),
+    ("cooperatives", "created_at", Some("DATETIME")),
+    ("cooperative_members", "coop_id", None),
+    ("cooperative_members", "member_id", None),
+    ("cooperative_members", "entry_date", None),
+    ("dues", "coop_member_id", None),
+    ("dues", "period", None),
+    ("dues", "amount", None),
+    ("dues", "paid_amount", Some("REAL DEFAULT 0")),
+    ("dues", "status", Some("TEXT DEFAULT 'unpaid'")),
+    ("dues", "payment_date", Some("TEXT")),
+    ("dues", "created_at", Some("DATETIME")),
+];
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Brings the database up to `latest_version()`.
+///
+/// Never drops user data: if the existing schema cannot be upgraded in place,
+/// or was written by a newer version of the application, an error describing
+/// the problem is returned and nothing is changed.
+pub async fn run(db: &Pool<Sqlite>, db_path: &Path) -> Result<(), String> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(db)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let current: i64 = sqlx::query("SELECT COALESCE(MAX(version), 0) AS version FROM schema_version")
+        .fetch_one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .try_get("version")
+        .map_err(|e| e.to_string())?;
+
+    let latest = latest_version();
+    if current > latest {
+        return Err(format!(
+            "Database schema version {} is newer than this application supports ({}). Please install the latest version of the application.",
+            current, latest
+        ));
+    }
+    if current == latest {
+        return Ok(());
+    }
+
+    // Before adopting a legacy schema too, which already alters its tables.
+    if has_user_tables(db).await? {
+        backup_before_upgrade(db, db_path, current).await?;
+    }
+
+    if current == 0 {
+        adopt_legacy_schema(db).await?;
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        apply(db, migration).await?;
+    }
+
+    Ok(())
+}
+
+async fn apply(db: &Pool<Sqlite>, migration: &Migration) -> Result<(), String> {
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+
+    let early = RUN_BEFORE
+        .iter()
+        .filter(|(before, _)| *before == migration.version)
+        .filter_map(|(_, version)| MIGRATIONS.iter().find(|m| m.version == *version));
+    for step in early.chain(std::iter::once(migration)) {
+        execute(&mut tx, step)
+            .await
+            .map_err(|e| format!("Migration {} ({}) failed: {}", step.version, step.description, e))?;
+    }
+
+    sqlx::query("INSERT INTO schema_version (version, description) VALUES (?, ?)")
+        .bind(migration.version)
+        .bind(migration.description)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+async fn execute(conn: &mut SqliteConnection, migration: &Migration) -> Result<(), String> {
+    if !migration.sql.is_empty() {
+        sqlx::query(migration.sql)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    match migration.data {
+        Some(step) => run_data_step(conn, step).await,
+        None => Ok(()),
+    }
+}
+
+async fn run_data_step(conn: &mut SqliteConnection, step: DataStep) -> Result<(), String> {
+    match step {
+        DataStep::NormalizePhones => normalize_phones(conn).await,
+        DataStep::RepairDates => repair_dates(conn).await,
+        DataStep::IndexMembers => index_members(conn).await,
+    }
+}
+
+/// Fills `member_search` using the current indexing code. An older index
+/// without the columns that code writes is left alone; the version that adds
+/// them recreates the index and fills it again.
+async fn index_members(conn: &mut SqliteConnection) -> Result<(), String> {
+    let columns: Vec<String> = sqlx::query("SELECT name FROM pragma_table_info('member_search')")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|r| r.try_get("name").unwrap_or_default())
+        .collect();
+    if !search::INDEXED_COLUMNS.iter().all(|c| columns.iter().any(|name| name == c)) {
+        return Ok(());
+    }
+
+    search::rebuild(conn).await.map_err(|e| e.to_string())
+}
+
+async fn normalize_phones(conn: &mut SqliteConnection) -> Result<(), String> {
+    let members = sqlx::query("SELECT id, phone_1, phone_2 FROM members")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for member in members {
+        let id: i64 = member.try_get("id").map_err(|e| e.to_string())?;
+        let phone_1: String = member.try_get("phone_1").map_err(|e| e.to_string())?;
+        let phone_2: Option<String> = member.try_get("phone_2").map_err(|e| e.to_string())?;
+
+        let new_phone_1 = validation::normalize_phone(&phone_1).unwrap_or(phone_1);
+        let new_phone_2 = phone_2
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| validation::normalize_phone(&p).unwrap_or(p));
+
+        sqlx::query("UPDATE members SET phone_1 = ?, phone_2 = ? WHERE id = ?")
+            .bind(new_phone_1)
+            .bind(new_phone_2)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+async fn repair_dates(conn: &mut SqliteConnection) -> Result<(), String> {
+    for (table, column) in dates::STORED_COLUMNS {
+        let rows = sqlx::query(&format!(
+            "SELECT id, CAST({column} AS TEXT) AS value FROM {table} WHERE {column} IS NOT NULL"
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for row in rows {
+            let id: i64 = row.try_get("id").map_err(|e| e.to_string())?;
+            let value: String = row.try_get("value").map_err(|e| e.to_string())?;
+            let Some(repaired) = dates::repair(column, &value).filter(|r| *r != value) else {
+                continue;
+            };
+
+            // A repaired period can collide with an existing monthly due; that row is left for review.
+            sqlx::query(&format!("UPDATE OR IGNORE {table} SET {column} = ? WHERE id = ?"))
+                .bind(repaired)
+                .bind(id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks tables created before versioning existed against `BASELINE_COLUMNS`,
+/// adding the optional columns that are missing.
+async fn adopt_legacy_schema(db: &Pool<Sqlite>) -> Result<(), String> {
+    let mut missing = Vec::new();
+    let mut additions = Vec::new();
+
+    for (table, column, definition) in BASELINE_COLUMNS {
+        let columns = table_columns(db, table).await?;
+        if columns.is_empty() || columns.iter().any(|c| c == column) {
+            continue;
+        }
+        match definition {
+            Some(definition) => additions.push(format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition)),
+            None => missing.push(format!("{}.{}", table, column)),
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "The existing database uses an unsupported schema (missing columns: {}). It was left untouched; please contact support before starting the application again.",
+            missing.join(", ")
+        ));
+    }
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    for statement in additions {
+        sqlx::query(&statement)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+async fn table_columns(db: &Pool<Sqlite>, table: &str) -> Result<Vec<String>, String> {
+    let rows = sqlx::query("SELECT name FROM pragma_table_info(?)")
+        .bind(table)
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|r| r.try_get("name").unwrap_or_default()).collect())
+}
+
+async fn has_user_tables(db: &Pool<Sqlite>) -> Result<bool, String> {
+    let count: i64 = sqlx::query(
+        "SELECT COUNT(*) AS count FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_version'"
+    )
+    .fetch_one(db)
+    .await
+    .map_err(|e| e.to_string())?
+    .try_get("count")
+    .map_err(|e| e.to_string())?;
+
+    Ok(count > 0)
+}
+
+/// Keeps a consistent copy of the database next to it before any migration
+/// touches existing data, e.g. `emlak.db.v1.bak`.
+async fn backup_before_upgrade(db: &Pool<Sqlite>, db_path: &Path, version: i64) -> Result<(), String> {
+    let backup_path = db_path.with_extension(format!("db.v{}.bak", version));
+    if backup_path.exists() {
+        return Ok(());
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup_path.to_string_lossy().to_string())
+        .execute(db)
+        .await
+        .map_err(|e| format!("Could not back up the database before upgrading: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// A private in-memory database; one connection, so every query sees it.
+    async fn memory_db() -> Pool<Sqlite> {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE schema_version (version INTEGER PRIMARY KEY, description TEXT NOT NULL, applied_at DATETIME)")
+            .execute(&db)
+            .await
+            .unwrap();
+        db
+    }
+
+    /// Applies the migrations after the current version up to and including `version`.
+    async fn migrate_to(db: &Pool<Sqlite>, version: i64) {
+        let current: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_version")
+            .fetch_one(db)
+            .await
+            .unwrap();
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current && m.version <= version) {
+            apply(db, migration).await.unwrap();
+        }
+    }
+
+    /// Member 1 in cooperative 1 as membership 1; works on every schema version.
+    async fn insert_membership(db: &Pool<Sqlite>) {
+        sqlx::query(
+            "INSERT INTO members (id, tc_number, full_name, phone_1, registration_date) VALUES (1, '10000000146', 'Ali Veli', '5321112233', '2024-01-01');
+            INSERT INTO cooperatives (id, name, start_date) VALUES (1, 'Koop', '2024-01-01');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date) VALUES (1, 1, 1, '2024-01-01');"
+        )
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn v2_converts_lira_to_kurus() {
+        let db = memory_db().await;
+        migrate_to(&db, 1).await;
+        insert_membership(&db).await;
+        sqlx::query(
+            "INSERT INTO dues (coop_member_id, period, amount, paid_amount, status) VALUES (1, '2024-01-01', 833.3333333, 833.33, 'partial');
+            INSERT INTO dues (coop_member_id, period, amount, paid_amount, status) VALUES (1, '2024-02-01', 0.1 + 0.2, NULL, NULL);
+            INSERT INTO dues (coop_member_id, period, amount, paid_amount, status) VALUES (1, '2024-03-01', 100, 99.999, 'partial');"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        migrate_to(&db, 2).await;
+
+        let dues: Vec<(i64, i64, String)> = sqlx::query_as("SELECT amount, paid_amount, status FROM dues ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            dues,
+            vec![
+                (83333, 83333, "paid".to_string()),
+                (30, 0, "unpaid".to_string()),
+                (10000, 10000, "paid".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn v5_moves_overpayments_to_credit() {
+        let db = memory_db().await;
+        migrate_to(&db, 2).await;
+        insert_membership(&db).await;
+        sqlx::query(
+            "INSERT INTO dues (id, coop_member_id, period, amount, paid_amount, status, payment_date) VALUES (1, 1, '2024-01-01', 10000, 15000, 'paid', '2024-01-10');
+            INSERT INTO dues (id, coop_member_id, period, amount, paid_amount, status) VALUES (2, 1, '2024-02-01', 10000, 4000, 'partial');"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        migrate_to(&db, 5).await;
+
+        let dues: Vec<(i64, i64, String)> = sqlx::query_as("SELECT id, paid_amount, status FROM dues ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(dues, vec![(1, 10000, "paid".to_string()), (2, 4000, "partial".to_string())]);
+
+        // The ledger still adds up to what each due shows as paid.
+        let ledger: Vec<(i64, i64)> = sqlx::query_as("SELECT due_id, SUM(amount) FROM payments GROUP BY due_id ORDER BY due_id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(ledger, vec![(1, 10000), (2, 4000)]);
+
+        let credits: Vec<(i64, i64, String, String)> = sqlx::query_as(
+            "SELECT c.coop_member_id, c.amount, c.entry_type, c.entry_date
+             FROM member_credits c JOIN payments p ON p.id = c.payment_id
+             WHERE p.method = 'credit' AND p.amount = -c.amount"
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(credits, vec![(1, 5000, "overpayment".to_string(), "2024-01-10".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn v16_merges_duplicates_that_are_transfer_targets() {
+        let db = memory_db().await;
+        migrate_to(&db, 15).await;
+        insert_membership(&db).await;
+        // Member 2 sold memberships 2 and 3 to member 1, who already held 1 and
+        // ends up with three active memberships; 4 is yet another duplicate.
+        sqlx::query(
+            "INSERT INTO members (id, tc_number, full_name, phone_1, registration_date) VALUES (2, '12345678950', 'Şule İnce', '+905321112234', '2024-01-01');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date, exit_date, exit_reason) VALUES (2, 1, 2, '2024-01-01', '2024-03-01', 'other');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date, exit_date, exit_reason) VALUES (3, 1, 2, '2024-01-01', '2024-04-01', 'other');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date) VALUES (4, 1, 1, '2024-03-01');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date) VALUES (5, 1, 1, '2024-04-01');
+            INSERT INTO cooperative_members (id, coop_id, member_id, entry_date) VALUES (6, 1, 1, '2024-05-01');
+            INSERT INTO membership_transfers (coop_id, from_coop_member_id, to_coop_member_id, transfer_date) VALUES (1, 2, 4, '2024-03-01');
+            INSERT INTO membership_transfers (coop_id, from_coop_member_id, to_coop_member_id, transfer_date) VALUES (1, 3, 5, '2024-04-01');
+            INSERT INTO dues (coop_member_id, period, amount) VALUES (1, '2024-01-01', 10000);
+            INSERT INTO dues (coop_member_id, period, amount) VALUES (5, '2024-04-01', 10000);
+            INSERT INTO dues (coop_member_id, period, amount) VALUES (6, '2024-04-01', 10000);
+            INSERT INTO member_credits (coop_member_id, amount, entry_type, entry_date) VALUES (6, 500, 'deposit', '2024-05-01');"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        migrate_to(&db, latest_version()).await;
+
+        let memberships: Vec<(i64, String, Option<String>)> =
+            sqlx::query_as("SELECT id, entry_date, exit_date FROM cooperative_members WHERE member_id = 1 ORDER BY id")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(
+            memberships,
+            vec![(4, "2024-01-01".to_string(), None), (5, "2024-04-01".to_string(), Some("2024-04-01".to_string()))]
+        );
+
+        // Everything lands on the kept membership; the clashing April due becomes an extra one.
+        let dues: Vec<(i64, String, String)> = sqlx::query_as("SELECT coop_member_id, period, kind FROM dues ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            dues,
+            vec![
+                (4, "2024-01-01".to_string(), "monthly".to_string()),
+                (4, "2024-04-01".to_string(), "monthly".to_string()),
+                (4, "2024-04-01".to_string(), "extra".to_string()),
+            ]
+        );
+        let credit: i64 = sqlx::query_scalar("SELECT SUM(amount) FROM member_credits WHERE coop_member_id = 4")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(credit, 500);
+
+        let transfers: Vec<(i64, i64)> = sqlx::query_as("SELECT from_coop_member_id, to_coop_member_id FROM membership_transfers ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(transfers, vec![(2, 4), (3, 5)]);
+        let broken: Vec<(String,)> = sqlx::query_as("SELECT \"table\" FROM pragma_foreign_key_check").fetch_all(&db).await.unwrap();
+        assert!(broken.is_empty());
+    }
+
+    #[tokio::test]
+    async fn v16_as_released_merges_into_the_oldest_membership() {
+        let db = memory_db().await;
+        migrate_to(&db, 15).await;
+        insert_membership(&db).await;
+        sqlx::query(
+            "INSERT INTO cooperative_members (id, coop_id, member_id, entry_date) VALUES (2, 1, 1, '2023-12-01');
+            INSERT INTO dues (coop_member_id, period, amount) VALUES (2, '2023-12-01', 10000);"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        migrate_to(&db, latest_version()).await;
+
+        let memberships: Vec<(i64, String)> = sqlx::query_as("SELECT id, entry_date FROM cooperative_members")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(memberships, vec![(1, "2023-12-01".to_string())]);
+        let owner: i64 = sqlx::query_scalar("SELECT coop_member_id FROM dues").fetch_one(&db).await.unwrap();
+        assert_eq!(owner, 1);
+    }
+
+    #[tokio::test]
+    async fn v21_moves_deposit_receipt_numbers_out_of_the_note() {
+        let db = memory_db().await;
+        migrate_to(&db, 20).await;
+        insert_membership(&db).await;
+        sqlx::query(
+            "INSERT INTO member_credits (coop_member_id, amount, entry_type, entry_date, note) VALUES (1, 5000, 'deposit', '2024-01-01', 'R-1');
+            INSERT INTO member_credits (coop_member_id, amount, entry_type, entry_date, note) VALUES (1, -5000, 'refund', '2024-01-02', 'Nakit');"
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        migrate_to(&db, 21).await;
+
+        let credits: Vec<(Option<String>, Option<String>)> = sqlx::query_as("SELECT note, receipt_no FROM member_credits ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(credits, vec![(None, Some("R-1".to_string())), (Some("Nakit".to_string()), None)]);
+    }
+}
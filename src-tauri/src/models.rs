@@ -1,6 +1,9 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::money::Money;
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Member {
     pub id: i64,
@@ -9,6 +12,15 @@ pub struct Member {
     pub phone_1: String,
     pub phone_2: Option<String>,
     pub registration_date: String,
+    pub province: Option<String>, // il
+    pub district: Option<String>, // ilçe
+    pub neighbourhood: Option<String>, // mahalle
+    pub address: Option<String>, // açık adres
+    pub email: Option<String>,
+    pub iban: Option<String>,
+    pub birth_date: Option<String>,
+    pub occupation: Option<String>,
+    pub notes: Option<String>,
     pub created_at: Option<String>,
 }
 
@@ -18,7 +30,70 @@ pub struct CreateMemberArgs {
     pub full_name: String,
     pub phone_1: String,
     pub phone_2: Option<String>,
-    pub registration_date: String,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub registration_date: NaiveDate,
+    pub province: Option<String>,
+    pub district: Option<String>,
+    pub neighbourhood: Option<String>,
+    pub address: Option<String>,
+    pub email: Option<String>,
+    pub iban: Option<String>,
+    #[serde(default, deserialize_with = "crate::dates::deserialize_option")]
+    pub birth_date: Option<NaiveDate>,
+    pub occupation: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberSort {
+    Name,
+    RegistrationDate,
+    Debt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MembershipStatus {
+    Active, // has an active membership
+    Former, // had a membership, but has left
+    NotMember, // has no active membership, i.e. can be added
+}
+
+/// Filters, order and page of `list_members`. Every field may be left out.
+/// `status` and the debt refer to `coop_id` when it is given, to any
+/// cooperative otherwise.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemberListQuery {
+    pub search: Option<String>,
+    pub coop_id: Option<i64>,
+    pub status: Option<MembershipStatus>,
+    pub has_debt: Option<bool>,
+    #[serde(deserialize_with = "crate::dates::deserialize_option")]
+    pub registered_from: Option<NaiveDate>, // inclusive
+    #[serde(deserialize_with = "crate::dates::deserialize_option")]
+    pub registered_to: Option<NaiveDate>,
+    pub sort: Option<MemberSort>, // defaults to name; when searching, only orders equally good matches
+    pub descending: bool,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>, // defaults to 50, at most 500
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct MemberListItem {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub member: Member,
+    pub debt: Money, // outstanding on dues that have fallen due
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemberPage {
+    pub members: Vec<MemberListItem>,
+    pub total: i64, // matching members over all pages
+    pub offset: i64,
+    pub limit: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -26,20 +101,40 @@ pub struct Cooperative {
     pub id: i64,
     pub name: String,
     pub start_date: String,
+    pub exit_refund_rate: f64, // % of what a leaving member paid that is refunded
+    pub status: CoopStatus,
     pub created_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum CoopStatus {
+    Active,
+    Completed, // tamamlandı
+    Liquidated, // tasfiye edildi
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateCoopArgs {
     pub name: String,
-    pub start_date: String,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub start_date: NaiveDate,
+    pub exit_refund_rate: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AddMemberToCoopArgs {
     pub coop_id: i64,
     pub member_ids: Vec<i64>,
-    pub entry_date: String,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub entry_date: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddMembersResult {
+    pub added: Vec<i64>, // member ids
+    pub skipped: Vec<i64>, // member ids already active in the cooperative
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -50,6 +145,67 @@ pub struct CoopMember {
     pub tc_number: String,
     pub phone_1: String,
     pub entry_date: String,
+    pub exit_date: Option<String>,
+    pub exit_reason: Option<ExitReason>,
+    pub transferred_from: Option<i64>, // previous holder's cooperative_members.id
+    pub transferred_to: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct OwnershipLink {
+    pub coop_member_id: i64,
+    pub member_id: i64,
+    pub full_name: String,
+    pub tc_number: String,
+    pub entry_date: String,
+    pub exit_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ExitReason {
+    Resigned, // istifa
+    Expelled, // ihraç
+    Deceased, // vefat
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaveCoopArgs {
+    pub coop_member_id: i64,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub exit_date: NaiveDate,
+    pub exit_reason: ExitReason,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExitSettlement {
+    pub coop_member_id: i64,
+    pub member_full_name: String,
+    pub entry_date: String,
+    pub exit_date: Option<String>,
+    pub exit_reason: Option<ExitReason>,
+    pub total_billed: Money,
+    pub total_paid: Money,
+    pub outstanding: Money,
+    pub credit_balance: Money,
+    pub exit_refund_rate: f64,
+    pub refundable_paid: Money, // paid on dues other than late fees
+    pub refund_amount: Money,
+    pub net_amount: Money, // positive: owed to the member, negative: still owed by them
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DueKind {
+    Monthly, // aidat
+    Extra, // ek ödeme
+    ShareCapital, // sermaye payı
+    Penalty, // gecikme zammı
+    Other,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -57,17 +213,60 @@ pub struct Due {
     pub id: i64,
     pub coop_member_id: i64,
     pub period: String,
-    pub amount: f64,
-    pub paid_amount: f64,
+    pub amount: Money,
+    pub paid_amount: Money,
     pub status: String,
     pub payment_date: Option<String>,
+    pub due_date: String,
+    pub penalty_of: Option<i64>, // the overdue due this late fee was charged for
+    pub kind: DueKind,
+    pub description: Option<String>,
+    pub deleted_at: Option<String>, // set while the due is in the recycle bin
+    pub deleted_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct DueKindTotal {
+    pub kind: DueKind,
+    pub count: i64,
+    pub amount: Money,
+    pub paid_amount: Money,
+    pub outstanding: Money,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PayDueArgs {
     pub due_id: i64,
-    pub amount: f64,
+    pub amount: Money,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub payment_date: NaiveDate,
+    pub method: Option<String>, // defaults to 'cash'
+    pub receipt_no: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Payment {
+    pub id: i64,
+    pub due_id: i64,
+    pub amount: Money,
     pub payment_date: String,
+    pub method: String,
+    pub receipt_no: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: Option<String>,
+    pub reversal_of: Option<i64>, // set on the entry that voids another payment
+    pub reversed_by: Option<i64>, // set on a payment that has been voided
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReversePaymentArgs {
+    pub payment_id: i64,
+    pub reason: String,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub reversal_date: NaiveDate,
+    pub created_by: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -77,3 +276,265 @@ pub struct ReceiptInfo {
     pub member_tc: String,
     pub member_phone: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CreditEntry {
+    pub id: i64,
+    pub coop_member_id: i64,
+    pub amount: Money, // positive adds to the balance, negative uses it up
+    pub entry_type: String, // overpayment, deposit, applied, refund, reversal, transfer
+    pub entry_date: String,
+    pub payment_id: Option<i64>,
+    pub due_id: Option<i64>,
+    pub note: Option<String>,
+    pub receipt_no: Option<String>, // deposits made through pay_member_balance
+    pub created_by: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemberCredit {
+    pub coop_member_id: i64,
+    pub balance: Money,
+    pub entries: Vec<CreditEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefundCreditArgs {
+    pub coop_member_id: i64,
+    pub amount: Money,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub refund_date: NaiveDate,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PayMemberBalanceArgs {
+    pub coop_member_id: i64,
+    pub amount: Money,
+    #[serde(deserialize_with = "crate::dates::deserialize")]
+    pub payment_date: NaiveDate,
+    pub method: Option<String>,
+    pub receipt_no: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaymentAllocation {
+    pub payment_id: i64,
+    pub due_id: i64,
+    pub period: String,
+    pub amount: Money,
+    pub remaining_due: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BalancePayment {
+    pub coop_member_id: i64,
+    pub payment_date: String,
+    pub receipt_no: Option<String>,
+    pub total: Money,
+    pub allocations: Vec<PaymentAllocation>,
+    pub credited: Money, // left over after every open due, kept as member credit
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct PenaltyRule {
+    pub coop_id: i64,
+    pub monthly_rate: f64, // percent per overdue month
+    pub compound: bool,
+    pub grace_days: i64,
+    pub cap_rate: Option<f64>, // upper bound as a percent of the overdue amount
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PenaltyLine {
+    pub due_id: i64,
+    pub coop_member_id: i64,
+    pub member_full_name: String,
+    pub period: String,
+    pub due_date: String,
+    pub outstanding: Money,
+    pub days_late: i64,
+    pub months_late: i64,
+    pub penalty: Money,
+    pub already_charged: Money,
+    pub to_charge: Money,
+    pub penalty_due_id: Option<i64>, // set when the line was posted
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub command: String,
+    pub entity: String, // member, cooperative, coop_member, due, payment, member_credit, penalty_rule
+    pub entity_id: i64,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditLogFilter {
+    pub entity: Option<String>,
+    pub entity_id: Option<i64>,
+    #[serde(default, deserialize_with = "crate::dates::deserialize_option")]
+    pub from: Option<NaiveDate>, // inclusive, local calendar days
+    #[serde(default, deserialize_with = "crate::dates::deserialize_option")]
+    pub to: Option<NaiveDate>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DuesPlanAmountType {
+    Monthly, // charged every month
+    Yearly, // spread over the months of the plan
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct DuesPlan {
+    pub id: i64,
+    pub coop_id: i64,
+    pub year: i32,
+    pub amount: Money,
+    pub amount_type: DuesPlanAmountType,
+    pub start_month: u32,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuesPlanArgs {
+    pub coop_id: i64,
+    pub year: i32,
+    pub amount: Money,
+    pub amount_type: DuesPlanAmountType,
+    pub start_month: Option<u32>, // 1 when left out
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuesPlanResult {
+    pub plan_id: i64,
+    pub member_count: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub skipped: i64, // paid periods and months before a member's entry
+    pub changes: Vec<DueChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DueChangeAction {
+    Insert,
+    Update,
+    Skip,
+}
+
+/// One row of what a generation command did, or would do in a dry run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DueChange {
+    pub coop_member_id: i64,
+    pub period: String,
+    pub action: DueChangeAction,
+    pub due_id: Option<i64>, // None for dues a dry run would insert
+    pub before_amount: Option<Money>,
+    pub after_amount: Option<Money>,
+    pub reason: Option<String>, // why it was skipped: paid, unchanged, exists, before_entry
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvalidTcNumber {
+    pub member_id: i64,
+    pub full_name: String,
+    pub tc_number: String,
+    pub code: String, // tc_length, tc_non_digit, tc_leading_zero, tc_checksum
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvalidPhone {
+    pub member_id: i64,
+    pub full_name: String,
+    pub field: String, // phone_1 or phone_2
+    pub phone: String,
+    pub code: String, // phone_format, phone_prefix
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvalidDate {
+    pub table: String,
+    pub column: String,
+    pub row_id: i64,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DocumentKind {
+    IdCard, // kimlik fotokopisi
+    Application, // üyelik başvurusu
+    Contract, // sözleşme
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Document {
+    pub id: i64,
+    pub member_id: Option<i64>,
+    pub coop_member_id: Option<i64>,
+    pub kind: DocumentKind,
+    pub file_name: String, // name of the file when it was attached
+    pub mime_type: String,
+    pub size: i64, // bytes
+    pub sha256: String,
+    pub note: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachDocumentArgs {
+    pub member_id: Option<i64>,
+    pub coop_member_id: Option<i64>,
+    pub kind: DocumentKind,
+    pub source_path: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub path: String,
+    pub documents: i64, // files copied from the document store
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatementLine {
+    pub date: String,
+    pub kind: String, // due, payment, reversal, deposit, refund, transfer
+    pub description: String,
+    pub debit: Money, // accrued or paid back to the member
+    pub credit: Money, // received from the member
+    pub balance: Money, // after this line; positive: owed by the member
+    pub due_id: Option<i64>,
+    pub payment_id: Option<i64>,
+    pub credit_entry_id: Option<i64>,
+    pub receipt_no: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemberStatement {
+    pub coop_member_id: i64,
+    pub coop_name: String,
+    pub member_full_name: String,
+    pub member_tc: String,
+    pub from: Option<String>,
+    pub to: String,
+    pub opening_balance: Money,
+    pub total_debit: Money,
+    pub total_credit: Money,
+    pub closing_balance: Money,
+    pub lines: Vec<StatementLine>,
+}
@@ -0,0 +1,197 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// An exact amount of Turkish lira, kept as integer kuruş.
+///
+/// Stored in SQLite as INTEGER. Over IPC it travels as a plain lira number
+/// (`833.33`) so the frontend keeps working with ordinary numbers; strings such
+/// as `"1.250,50"` are accepted on input as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    #[cfg(test)]
+    pub fn from_kurus(kurus: i64) -> Self {
+        Money(kurus)
+    }
+
+    /// Rounds a lira amount to the nearest kuruş.
+    pub fn from_lira(lira: f64) -> Self {
+        Money((lira * 100.0).round() as i64)
+    }
+
+    #[cfg(test)]
+    pub fn kurus(self) -> i64 {
+        self.0
+    }
+
+    pub fn to_lira(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Multiplies by `factor`, rounding to the nearest kuruş.
+    pub fn scale(self, factor: f64) -> Money {
+        Money((self.0 as f64 * factor).round() as i64)
+    }
+
+    /// Splits the amount into `parts` installments that differ by at most one
+    /// kuruş and add up exactly to the original; earlier installments carry the
+    /// remainder (1000,00 / 12 = 4 x 83,34 + 8 x 83,33).
+    pub fn split(self, parts: u32) -> Vec<Money> {
+        if parts == 0 {
+            return Vec::new();
+        }
+        let parts = i64::from(parts);
+        let base = self.0.div_euclid(parts);
+        let remainder = self.0.rem_euclid(parts);
+        (0..parts)
+            .map(|i| Money(base + if i < remainder { 1 } else { 0 }))
+            .collect()
+    }
+
+    fn parse(input: &str) -> Option<Money> {
+        let cleaned: String = input.trim().chars().filter(|c| !c.is_whitespace()).collect();
+        // "1.250,50" is the Turkish notation; without a comma a dot is the decimal separator.
+        let normalized = if cleaned.contains(',') {
+            cleaned.replace('.', "").replace(',', ".")
+        } else {
+            cleaned
+        };
+        let lira: f64 = normalized.parse().ok()?;
+        lira.is_finite().then(|| Money::from_lira(lira))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, |acc, m| acc + m)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_lira())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+
+        impl<'de> Visitor<'de> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a lira amount as a number or string")
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+                v.checked_mul(100).map(Money).ok_or_else(|| E::custom("amount out of range"))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+                i64::try_from(v)
+                    .ok()
+                    .and_then(|v| v.checked_mul(100))
+                    .map(Money)
+                    .ok_or_else(|| E::custom("amount out of range"))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+                if v.is_finite() {
+                    Ok(Money::from_lira(v))
+                } else {
+                    Err(E::custom("amount must be a finite number"))
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+                Money::parse(v).ok_or_else(|| E::custom(format!("invalid amount: {}", v)))
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_adds_up_and_front_loads_the_remainder() {
+        let parts = Money::from_kurus(100_000).split(12);
+        assert_eq!(parts.len(), 12);
+        assert!(parts[..4].iter().all(|p| p.kurus() == 8334));
+        assert!(parts[4..].iter().all(|p| p.kurus() == 8333));
+        assert_eq!(parts.into_iter().sum::<Money>(), Money::from_kurus(100_000));
+        assert!(Money::from_kurus(100).split(0).is_empty());
+    }
+
+    #[test]
+    fn parse_turkish_and_plain_notation() {
+        assert_eq!(Money::parse("1.250,50"), Some(Money::from_kurus(125_050)));
+        assert_eq!(Money::parse(" 1 250,5 "), Some(Money::from_kurus(125_050)));
+        assert_eq!(Money::parse("833.33"), Some(Money::from_kurus(83_333)));
+        assert_eq!(Money::parse("abc"), None);
+        assert_eq!(Money::parse("inf"), None);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::models::PenaltyRule;
+use crate::money::Money;
+
+/// Late fee owed on `outstanding` when it is `days_late` days past its due
+/// date. Nothing is charged within the grace period; after it, every started
+/// month since the due date counts as a full month.
+pub fn late_fee(rule: &PenaltyRule, outstanding: Money, days_late: i64) -> Money {
+    if !outstanding.is_positive() || days_late <= rule.grace_days {
+        return Money::ZERO;
+    }
+
+    let months = overdue_months(days_late);
+    let rate = rule.monthly_rate / 100.0;
+    let factor = if rule.compound {
+        (1.0 + rate).powi(months as i32) - 1.0
+    } else {
+        rate * months as f64
+    };
+
+    let fee = outstanding.scale(factor);
+    match rule.cap_rate {
+        Some(cap_rate) => fee.min(outstanding.scale(cap_rate / 100.0)),
+        None => fee,
+    }
+}
+
+pub fn overdue_months(days_late: i64) -> i64 {
+    if days_late <= 0 {
+        0
+    } else {
+        (days_late + 29) / 30
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(compound: bool, cap_rate: Option<f64>) -> PenaltyRule {
+        PenaltyRule { coop_id: 1, monthly_rate: 2.0, compound, grace_days: 10, cap_rate }
+    }
+
+    #[test]
+    fn nothing_within_grace_or_when_paid() {
+        assert_eq!(late_fee(&rule(false, None), Money::from_kurus(100_000), 10), Money::ZERO);
+        assert_eq!(late_fee(&rule(false, None), Money::ZERO, 90), Money::ZERO);
+    }
+
+    #[test]
+    fn started_months_count_in_full() {
+        // 31 days late is two started months: 2% x 2 of 1000,00.
+        assert_eq!(late_fee(&rule(false, None), Money::from_kurus(100_000), 31), Money::from_kurus(4_000));
+        // Compounded: 1000,00 x (1.02^2 - 1) = 40,40.
+        assert_eq!(late_fee(&rule(true, None), Money::from_kurus(100_000), 31), Money::from_kurus(4_040));
+    }
+
+    #[test]
+    fn fee_is_capped() {
+        assert_eq!(late_fee(&rule(false, Some(5.0)), Money::from_kurus(100_000), 365), Money::from_kurus(5_000));
+    }
+}
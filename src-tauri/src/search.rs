@@ -0,0 +1,118 @@
+use sqlx::{Row, SqliteConnection};
+
+/// Folds text for searching: Turkish letters are lowercased by Turkish rules
+/// and stripped of their marks (`İ`, `I`, `ı` all become `i`, `Ş` becomes
+/// `s`), and punctuation becomes a word break.
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'İ' | 'I' | 'ı' | 'î' | 'Î' => folded.push('i'),
+            'Ş' | 'ş' => folded.push('s'),
+            'Ğ' | 'ğ' => folded.push('g'),
+            'Ü' | 'ü' | 'Û' | 'û' => folded.push('u'),
+            'Ö' | 'ö' => folded.push('o'),
+            'Ç' | 'ç' => folded.push('c'),
+            'Â' | 'â' => folded.push('a'),
+            c if c.is_alphanumeric() => folded.extend(c.to_lowercase()),
+            _ => folded.push(' '),
+        }
+    }
+    folded
+}
+
+/// Every form a phone number may be typed in: `+905321112233` is indexed as
+/// `905321112233`, `05321112233` and `5321112233`.
+fn phone_terms(phone: &str) -> String {
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    match digits.strip_prefix("90") {
+        Some(national) if phone.starts_with('+') => format!("{} 0{} {}", digits, national, national),
+        _ => digits,
+    }
+}
+
+/// Turns what the user typed into an FTS5 query matching members whose
+/// words start with every term, or `None` if nothing searchable was typed.
+pub fn match_query(query: &str) -> Option<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in fold(query).split_whitespace() {
+        let is_number = |t: &str| t.chars().all(|c| c.is_ascii_digit());
+        // Numbers are typed in groups ("0532 111 22 33"); search them as one.
+        if is_number(word) {
+            if let Some(last) = terms.last_mut().filter(|t| is_number(t)) {
+                last.push_str(word);
+                continue;
+            }
+        }
+        terms.push(word.to_string());
+    }
+
+    if terms.is_empty() {
+        return None;
+    }
+    Some(terms.iter().map(|t| format!("\"{}\"*", t)).collect::<Vec<_>>().join(" "))
+}
+
+/// Columns of `member_search` that [`index_member`] writes.
+pub const INDEXED_COLUMNS: &[&str] = &["full_name", "tc_number", "phones", "notes"];
+
+/// Orders `member_search` matches best first: a hit in the name counts most,
+/// then TC Kimlik No and phones, then notes.
+pub const RANK: &str = "bm25(member_search, 10.0, 5.0, 5.0, 1.0)";
+
+/// Brings the `member_search` entry of a member up to date with `members`.
+/// Call it on the same transaction as the write.
+pub async fn index_member(conn: &mut SqliteConnection, member_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM member_search WHERE rowid = ?")
+        .bind(member_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let member = sqlx::query("SELECT full_name, tc_number, phone_1, phone_2, notes FROM members WHERE id = ?")
+        .bind(member_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    let Some(member) = member else {
+        return Ok(());
+    };
+
+    let full_name: String = member.try_get("full_name")?;
+    let tc_number: String = member.try_get("tc_number")?;
+    let phone_1: String = member.try_get("phone_1")?;
+    let phone_2: Option<String> = member.try_get("phone_2")?;
+    let notes: Option<String> = member.try_get("notes")?;
+    let phones = [Some(phone_1), phone_2]
+        .iter()
+        .flatten()
+        .map(|p| phone_terms(p))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    sqlx::query("INSERT INTO member_search (rowid, full_name, tc_number, phones, notes) VALUES (?, ?, ?, ?, ?)")
+        .bind(member_id)
+        .bind(fold(&full_name))
+        .bind(tc_number)
+        .bind(phones)
+        .bind(notes.as_deref().map(fold))
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Indexes every member again.
+pub async fn rebuild(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM member_search").execute(&mut *conn).await?;
+
+    let ids: Vec<i64> = sqlx::query("SELECT id FROM members")
+        .fetch_all(&mut *conn)
+        .await?
+        .iter()
+        .map(|row| row.try_get("id"))
+        .collect::<Result<_, _>>()?;
+    for id in ids {
+        index_member(conn, id).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+/// Why a TC Kimlik No was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TcError {
+    Length,
+    NonDigit,
+    LeadingZero,
+    Checksum,
+}
+
+impl TcError {
+    pub fn code(self) -> &'static str {
+        match self {
+            TcError::Length => "tc_length",
+            TcError::NonDigit => "tc_non_digit",
+            TcError::LeadingZero => "tc_leading_zero",
+            TcError::Checksum => "tc_checksum",
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            TcError::Length => "TC Kimlik No must have 11 digits",
+            TcError::NonDigit => "TC Kimlik No may only contain digits",
+            TcError::LeadingZero => "TC Kimlik No cannot start with 0",
+            TcError::Checksum => "TC Kimlik No check digits do not match",
+        }
+    }
+
+    pub fn message_tr(self) -> &'static str {
+        match self {
+            TcError::Length => "TC Kimlik No 11 haneli olmalıdır.",
+            TcError::NonDigit => "TC Kimlik No yalnızca rakamlardan oluşmalıdır.",
+            TcError::LeadingZero => "TC Kimlik No 0 ile başlayamaz.",
+            TcError::Checksum => "TC Kimlik No kontrol haneleri tutmuyor.",
+        }
+    }
+}
+
+/// Drops the spaces people type into TC numbers ("123 456 789 01").
+pub fn normalize_tc(input: &str) -> String {
+    input.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Checks a normalized TC Kimlik No against the official rules: 11 digits,
+/// no leading zero, the 10th digit is `(7 * odd - even) mod 10` over the
+/// first nine and the 11th is the sum of the first ten mod 10.
+pub fn check_tc(tc: &str) -> Result<(), TcError> {
+    if !tc.chars().all(|c| c.is_ascii_digit()) {
+        return Err(TcError::NonDigit);
+    }
+    if tc.len() != 11 {
+        return Err(TcError::Length);
+    }
+
+    let d: Vec<i32> = tc.bytes().map(|b| i32::from(b - b'0')).collect();
+    if d[0] == 0 {
+        return Err(TcError::LeadingZero);
+    }
+
+    let odd = d[0] + d[2] + d[4] + d[6] + d[8];
+    let even = d[1] + d[3] + d[5] + d[7];
+    let tenth = (odd * 7 - even).rem_euclid(10);
+    let eleventh = d[..10].iter().sum::<i32>() % 10;
+    if d[9] != tenth || d[10] != eleventh {
+        return Err(TcError::Checksum);
+    }
+
+    Ok(())
+}
+
+/// Why a phone number could not be normalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneError {
+    Format,
+    Prefix,
+}
+
+impl PhoneError {
+    pub fn code(self) -> &'static str {
+        match self {
+            PhoneError::Format => "phone_format",
+            PhoneError::Prefix => "phone_prefix",
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            PhoneError::Format => "Phone number must be a 10 digit Turkish number",
+            PhoneError::Prefix => "Phone number is neither a mobile nor a landline number",
+        }
+    }
+
+    pub fn message_tr(self) -> &'static str {
+        match self {
+            PhoneError::Format => "Telefon numarası 10 haneli bir Türkiye numarası olmalıdır.",
+            PhoneError::Prefix => "Telefon numarası geçerli bir cep veya sabit hat numarası değil.",
+        }
+    }
+}
+
+/// Brings a Turkish phone number written as "0532 111 22 33", "+90532…",
+/// "532-111-2233" etc. to E.164 (`+905321112233`). Only mobile (5xx) and
+/// landline (2xx, 3xx, 4xx and 850) numbers are accepted.
+pub fn normalize_phone(input: &str) -> Result<String, PhoneError> {
+    let cleaned: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '(' | ')' | '.' | '/'))
+        .collect();
+
+    let national = if let Some(rest) = cleaned.strip_prefix("+90") {
+        rest
+    } else if let Some(rest) = cleaned.strip_prefix("0090") {
+        rest
+    } else if cleaned.len() == 12 && cleaned.starts_with("90") {
+        &cleaned[2..]
+    } else if cleaned.len() == 11 && cleaned.starts_with('0') {
+        &cleaned[1..]
+    } else {
+        &cleaned
+    };
+
+    if national.len() != 10 || !national.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PhoneError::Format);
+    }
+    if !is_mobile(national) && !is_landline(national) {
+        return Err(PhoneError::Prefix);
+    }
+
+    Ok(format!("+90{}", national))
+}
+
+/// 50x, 53x, 54x, 55x and 56x operator prefixes.
+fn is_mobile(national: &str) -> bool {
+    national.starts_with('5') && matches!(national.as_bytes()[1], b'0' | b'3' | b'4' | b'5' | b'6')
+}
+
+/// Area codes (2xx–4xx) and the nationwide 850 numbers.
+fn is_landline(national: &str) -> bool {
+    matches!(national.as_bytes()[0], b'2' | b'3' | b'4') || national.starts_with("850")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IbanError {
+    Format,
+    Country,
+    Checksum,
+}
+
+impl IbanError {
+    pub fn code(self) -> &'static str {
+        match self {
+            IbanError::Format => "iban_format",
+            IbanError::Country => "iban_country",
+            IbanError::Checksum => "iban_checksum",
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            IbanError::Format => "IBAN must be TR followed by 24 digits",
+            IbanError::Country => "Only Turkish (TR) IBANs are accepted",
+            IbanError::Checksum => "IBAN check digits do not match",
+        }
+    }
+
+    pub fn message_tr(self) -> &'static str {
+        match self {
+            IbanError::Format => "IBAN, TR ve ardından 24 rakamdan oluşmalıdır.",
+            IbanError::Country => "Yalnızca Türkiye (TR) IBAN'ları kabul edilir.",
+            IbanError::Checksum => "IBAN kontrol haneleri tutmuyor.",
+        }
+    }
+}
+
+/// Brings an IBAN written as "TR33 0006 1005 …" to its compact upper case
+/// form and checks it: 26 characters, `TR` and the ISO 13616 mod-97 check.
+pub fn normalize_iban(input: &str) -> Result<String, IbanError> {
+    let iban: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .collect();
+
+    if iban.len() < 2 || !iban.is_char_boundary(2) {
+        return Err(IbanError::Format);
+    }
+    if &iban[..2] != "TR" {
+        return Err(IbanError::Country);
+    }
+    if iban.len() != 26 || !iban[2..].chars().all(|c| c.is_ascii_digit()) {
+        return Err(IbanError::Format);
+    }
+
+    // Moving the first four characters to the end must leave 1 modulo 97, letters counting as 10–35.
+    let remainder = iban[4..].chars().chain(iban[..4].chars()).fold(0u32, |acc, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 {
+            (acc * 100 + value) % 97
+        } else {
+            (acc * 10 + value) % 97
+        }
+    });
+    if remainder != 1 {
+        return Err(IbanError::Checksum);
+    }
+
+    Ok(iban)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailError {
+    Format,
+}
+
+impl EmailError {
+    pub fn code(self) -> &'static str {
+        "email_format"
+    }
+
+    pub fn message(self) -> &'static str {
+        "E-mail address is not valid"
+    }
+
+    pub fn message_tr(self) -> &'static str {
+        "E-posta adresi geçerli değil."
+    }
+}
+
+/// Lowercases an e-mail address and checks that it looks like `name@domain.tld`.
+pub fn normalize_email(input: &str) -> Result<String, EmailError> {
+    let email = input.trim().to_lowercase();
+    let (local, domain) = email.split_once('@').ok_or(EmailError::Format)?;
+
+    let valid = !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email.chars().any(char::is_whitespace);
+    if !valid {
+        return Err(EmailError::Format);
+    }
+
+    Ok(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tc_checksum() {
+        assert_eq!(check_tc("10000000146"), Ok(()));
+        assert_eq!(check_tc("10000000145"), Err(TcError::Checksum));
+        assert_eq!(check_tc("01234567890"), Err(TcError::LeadingZero));
+        assert_eq!(check_tc("1234"), Err(TcError::Length));
+        assert_eq!(check_tc("1000000014a"), Err(TcError::NonDigit));
+    }
+
+    #[test]
+    fn phone_forms() {
+        for input in ["0532 111 22 33", "+90 532 111 22 33", "00905321112233", "(532) 111-22-33"] {
+            assert_eq!(normalize_phone(input).as_deref(), Ok("+905321112233"));
+        }
+        assert_eq!(normalize_phone("0850 222 33 44").as_deref(), Ok("+908502223344"));
+        assert_eq!(normalize_phone("0532 111 22"), Err(PhoneError::Format));
+        assert_eq!(normalize_phone("0112 111 22 33"), Err(PhoneError::Prefix));
+    }
+
+    #[test]
+    fn iban_forms() {
+        assert_eq!(
+            normalize_iban("tr33 0006 1005 1978 6457 8413 26").as_deref(),
+            Ok("TR330006100519786457841326")
+        );
+        assert_eq!(normalize_iban("TR340006100519786457841326"), Err(IbanError::Checksum));
+        assert_eq!(normalize_iban("DE89370400440532013000"), Err(IbanError::Country));
+        assert_eq!(normalize_iban("TR33000610051978645784"), Err(IbanError::Format));
+    }
+}
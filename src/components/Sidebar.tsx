@@ -1,5 +1,8 @@
 import { Link, useLocation } from 'react-router-dom';
-import { Users, Building, PlusCircle } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Users, Building, PlusCircle, HardDrive } from 'lucide-react';
+import { formatCommandError } from '../utils/commandError';
 import './Sidebar.css';
 
 export function Sidebar() {
@@ -8,6 +11,17 @@ export function Sidebar() {
     // Helper to check if active
     const isActive = (path: string) => location.pathname === path;
 
+    const handleBackup = async () => {
+        const destination = await open({ directory: true, title: 'Yedeğin kaydedileceği klasörü seçin' });
+        if (!destination) return;
+        try {
+            const backup = await invoke<{ path: string; documents: number }>('create_backup', { destination });
+            alert(`Yedek alındı (${backup.documents} belge):\n${backup.path}`);
+        } catch (error) {
+            alert(`Yedek alınamadı:\n${formatCommandError(error)}`);
+        }
+    };
+
     return (
         <aside className="sidebar glass-panel">
             <div className="sidebar-header">
@@ -38,6 +52,14 @@ export function Sidebar() {
                         <span>Kooperatifler</span>
                     </Link>
                 </div>
+
+                <div className="nav-section">
+                    <div className="nav-label">Sistem</div>
+                    <button className="nav-item" onClick={handleBackup}>
+                        <HardDrive className="nav-icon" />
+                        <span>Yedek Al</span>
+                    </button>
+                </div>
             </nav>
         </aside>
     );
@@ -2,6 +2,7 @@ import { useEffect, useState } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
 import { UserPlus, ArrowLeft } from 'lucide-react';
+import { formatCommandError } from '../utils/commandError';
 import './CoopDetail.css';
 import '../pages/MemberList.css'; // Reuse table styles
 
@@ -66,18 +67,29 @@ export default function CoopDetail() {
         }
     };
 
-    const openAddModal = async () => {
+    const fetchAvailableMembers = async (search: string) => {
         try {
-            const result = await invoke<AvailableMember[]>('get_available_members', { coopId: Number(id) });
-            setAvailableMembers(result);
-            setSearchQuery('');
-            setSelectedMemberForAdd(null);
-            setShowAddModal(true);
+            const result = await invoke<{ members: AvailableMember[] }>('list_members', {
+                query: { coop_id: Number(id), status: 'not_member', search: search || null, limit: 100 }
+            });
+            setAvailableMembers(result.members);
         } catch (error) {
             console.error('Uygun üyeler alınamadı:', error);
         }
     };
 
+    const openAddModal = async () => {
+        await fetchAvailableMembers('');
+        setSearchQuery('');
+        setSelectedMemberForAdd(null);
+        setShowAddModal(true);
+    };
+
+    const handleAvailableSearch = (search: string) => {
+        setSearchQuery(search);
+        fetchAvailableMembers(search);
+    };
+
     const handleMemberClick = (member: AvailableMember) => {
         setSelectedMemberForAdd(member);
         // Default entry date stays as is (coop start date or previously set)
@@ -87,14 +99,18 @@ export default function CoopDetail() {
         if (!selectedMemberForAdd || !entryDate) return;
 
         try {
-            await invoke('add_members_to_coop', {
+            const result = await invoke<{ added: number[]; skipped: number[] }>('add_members_to_coop', {
                 args: {
                     coop_id: Number(id),
                     member_ids: [selectedMemberForAdd.id],
                     entry_date: entryDate
                 }
             });
-            alert(`${selectedMemberForAdd.full_name} kooperatife eklendi!`);
+            if (result.skipped.length > 0) {
+                alert(`${selectedMemberForAdd.full_name} zaten bu kooperatifin üyesi.`);
+            } else {
+                alert(`${selectedMemberForAdd.full_name} kooperatife eklendi!`);
+            }
 
             // Remove from available list immediately to prevent duplicate add attempts without re-fetch
             setAvailableMembers(prev => prev.filter(m => m.id !== selectedMemberForAdd.id));
@@ -102,15 +118,10 @@ export default function CoopDetail() {
             fetchCoopMembers();
         } catch (error) {
             console.error('Üye ekleme hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     };
 
-    const filteredAvailableMembers = availableMembers.filter(m =>
-        m.full_name.toLowerCase().includes(searchQuery.toLowerCase()) ||
-        m.tc_number.includes(searchQuery)
-    );
-
     if (!coop) return <div className="p-4">Yükleniyor...</div>;
 
     return (
@@ -186,7 +197,7 @@ export default function CoopDetail() {
                                     className="form-input"
                                     placeholder="Üye ara (İsim veya TC)..."
                                     value={searchQuery}
-                                    onChange={(e) => setSearchQuery(e.target.value)}
+                                    onChange={(e) => handleAvailableSearch(e.target.value)}
                                     autoFocus
                                 />
                             </div>
@@ -203,10 +214,10 @@ export default function CoopDetail() {
                             </div>
 
                             <div className="member-select-list" style={{ marginTop: '1rem' }}>
-                                {filteredAvailableMembers.length === 0 ? (
+                                {availableMembers.length === 0 ? (
                                     <div className="p-4 text-center text-muted">Üye bulunamadı.</div>
                                 ) : (
-                                    filteredAvailableMembers.map(member => (
+                                    availableMembers.map(member => (
                                         <div
                                             key={member.id}
                                             className="member-select-item"
@@ -4,10 +4,21 @@ import { invoke } from '@tauri-apps/api/core';
 import { ArrowLeft, CreditCard, RotateCw, Wallet, Edit2, Plus, ChevronLeft, ChevronRight, ChevronDown, ChevronUp, Trash2, FileText } from 'lucide-react';
 import { Document, Packer, Paragraph, TextRun, Table, TableRow, TableCell, WidthType, AlignmentType, BorderStyle } from 'docx';
 import { save } from '@tauri-apps/plugin-dialog';
+import { commandErrorCode, formatCommandError } from '../utils/commandError';
 import { writeFile } from '@tauri-apps/plugin-fs';
 import { numberToTurkishWords } from '../utils/numberToText';
 import './CoopMemberDues.css';
 
+interface DueChange {
+    coop_member_id: number;
+    period: string;
+    action: 'insert' | 'update' | 'skip';
+    due_id: number | null;
+    before_amount: number | null;
+    after_amount: number | null;
+    reason: string | null;
+}
+
 interface Due {
     id: number;
     coop_member_id: number;
@@ -18,6 +29,29 @@ interface Due {
     payment_date: string | null;
 }
 
+interface StatementLine {
+    date: string;
+    kind: string;
+    description: string;
+    debit: number;
+    credit: number;
+    balance: number;
+    receipt_no: string | null;
+}
+
+interface MemberStatement {
+    coop_name: string;
+    member_full_name: string;
+    member_tc: string;
+    from: string | null;
+    to: string;
+    opening_balance: number;
+    total_debit: number;
+    total_credit: number;
+    closing_balance: number;
+    lines: StatementLine[];
+}
+
 interface ReceiptInfo {
     coop_name: string;
     member_full_name: string;
@@ -69,11 +103,23 @@ export default function CoopMemberDues() {
         }
 
         try {
-            await invoke('generate_yearly_dues', {
+            const args = {
                 coopMemberId: Number(memberId),
                 year: selectedYear,
                 totalAmount: Number(yearlyTotalAmount)
-            });
+            };
+            const preview = await invoke<DueChange[]>('generate_yearly_dues', { ...args, dryRun: true });
+            const inserts = preview.filter(c => c.action === 'insert').length;
+            const updates = preview.filter(c => c.action === 'update');
+            const skips = preview.filter(c => c.action === 'skip').length;
+            const updateLines = updates
+                .map(c => `${c.period}: ${c.before_amount} TL → ${c.after_amount} TL`)
+                .join('\n');
+            const summary = `${inserts} yeni aidat eklenecek, ${updates.length} aidat güncellenecek, ${skips} aidat değişmeyecek.`
+                + (updateLines ? `\n\nGüncellenecekler:\n${updateLines}` : '');
+            if (!confirm(`${summary}\n\nDevam edilsin mi?`)) return;
+
+            await invoke('generate_yearly_dues', args);
             fetchDues();
 
             // Auto expand the selected year if not expanded
@@ -84,7 +130,7 @@ export default function CoopMemberDues() {
             alert(`${selectedYear} yılı için aidatlar oluşturuldu/güncellendi.`);
         } catch (error) {
             console.error('Aidat oluşturma hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     };
 
@@ -106,7 +152,7 @@ export default function CoopMemberDues() {
             fetchDues();
         } catch (error) {
             console.error('Ekleme hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     }
 
@@ -131,7 +177,7 @@ export default function CoopMemberDues() {
             fetchDues();
         } catch (error) {
             console.error('Güncelleme hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     };
 
@@ -140,12 +186,19 @@ export default function CoopMemberDues() {
         if (!confirm('Bu aidatı silmek istediğinize emin misiniz?')) return;
 
         try {
-            await invoke('delete_due', { id: selectedDue.id });
+            try {
+                await invoke('delete_due', { id: selectedDue.id, force: false });
+            } catch (error) {
+                // Also raised for dues whose payments were all reversed, so paid_amount alone can't tell.
+                if (commandErrorCode(error) !== 'due_has_payments') throw error;
+                if (!confirm('Bu aidata ödeme kaydı var. Ödenmiş tutar varsa üye alacağına aktarılacak. Devam edilsin mi?')) return;
+                await invoke('delete_due', { id: selectedDue.id, force: true });
+            }
             setShowDetailModal(false);
             fetchDues();
         } catch (error) {
             console.error('Silme hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     };
 
@@ -164,7 +217,7 @@ export default function CoopMemberDues() {
             fetchDues();
         } catch (error) {
             console.error('Ödeme hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     };
 
@@ -343,7 +396,102 @@ export default function CoopMemberDues() {
 
         } catch (error) {
             console.error('Belge oluşturma hatası:', error);
-            alert(`Hata: ${error}`);
+            alert(`Hata: ${formatCommandError(error)}`);
+        }
+    };
+
+    // Statement of the selected year, up to today for the current year.
+    const handleExportStatement = async () => {
+        try {
+            const isCurrentYear = selectedYear === new Date().getFullYear();
+            const statement = await invoke<MemberStatement>('get_member_statement', {
+                coopMemberId: Number(memberId),
+                from: `${selectedYear}-01-01`,
+                to: isCurrentYear ? null : `${selectedYear}-12-31`
+            });
+
+            const money = (value: number) => value.toLocaleString('tr-TR', { minimumFractionDigits: 2 });
+            const date = (value: string) => new Date(value).toLocaleDateString('tr-TR');
+            // Date and description on the left, amounts on the right.
+            const row = (cells: string[], bold = false) => new TableRow({
+                children: cells.map((text, i) => new TableCell({
+                    children: [new Paragraph({
+                        alignment: i < 2 ? AlignmentType.LEFT : AlignmentType.RIGHT,
+                        children: [new TextRun({ text, bold })]
+                    })]
+                })),
+            });
+
+            const doc = new Document({
+                sections: [{
+                    properties: {},
+                    children: [
+                        new Paragraph({
+                            text: "HESAP EKSTRESİ",
+                            heading: "Heading1",
+                            alignment: AlignmentType.CENTER,
+                            spacing: { after: 400 }
+                        }),
+                        new Paragraph({
+                            children: [new TextRun({ text: "Kooperatif Adı: ", bold: true }), new TextRun(statement.coop_name)],
+                            spacing: { after: 100 }
+                        }),
+                        new Paragraph({
+                            children: [new TextRun({ text: "Üye: ", bold: true }), new TextRun(`${statement.member_full_name} (${statement.member_tc})`)],
+                            spacing: { after: 100 }
+                        }),
+                        new Paragraph({
+                            children: [
+                                new TextRun({ text: "Dönem: ", bold: true }),
+                                new TextRun(`${statement.from ? date(statement.from) : '-'} - ${date(statement.to)}`),
+                            ],
+                            spacing: { after: 400 }
+                        }),
+                        new Table({
+                            width: { size: 100, type: WidthType.PERCENTAGE },
+                            rows: [
+                                row(['Tarih', 'Açıklama', 'Borç', 'Alacak', 'Bakiye'], true),
+                                row(['', 'Devreden bakiye', '', '', money(statement.opening_balance)]),
+                                ...statement.lines.map(line => row([
+                                    date(line.date),
+                                    line.receipt_no ? `${line.description} (Makbuz: ${line.receipt_no})` : line.description,
+                                    line.debit ? money(line.debit) : '',
+                                    line.credit ? money(line.credit) : '',
+                                    money(line.balance),
+                                ])),
+                                row(['', 'Toplam', money(statement.total_debit), money(statement.total_credit), money(statement.closing_balance)], true),
+                            ],
+                        }),
+                        new Paragraph({
+                            children: [
+                                new TextRun({ text: statement.closing_balance >= 0 ? "Kalan Borç: " : "Üye Alacağı: ", bold: true }),
+                                new TextRun(`${money(Math.abs(statement.closing_balance))} TL`),
+                            ],
+                            spacing: { before: 400 }
+                        }),
+                    ],
+                }],
+            });
+
+            const blob = await Packer.toBlob(doc);
+            const fileName = `Hesap_Ekstresi_${statement.member_full_name.replace(/\s+/g, '_')}_${selectedYear}.docx`;
+
+            const filePath = await save({
+                defaultPath: fileName,
+                filters: [{
+                    name: 'Word Belgesi',
+                    extensions: ['docx']
+                }]
+            });
+
+            if (filePath) {
+                const arrayBuffer = await blob.arrayBuffer();
+                await writeFile(filePath, new Uint8Array(arrayBuffer));
+                alert('Belge başarıyla kaydedildi!');
+            }
+        } catch (error) {
+            console.error('Ekstre oluşturma hatası:', error);
+            alert(`Hata: ${formatCommandError(error)}`);
         }
     };
 
@@ -426,6 +574,11 @@ export default function CoopMemberDues() {
                         <RotateCw size={14} />
                         Hesapla / Dağıt
                     </button>
+
+                    <button className="btn-secondary-xs flex items-center gap-1" onClick={handleExportStatement} title="Seçili yılın hesap ekstresi">
+                        <FileText size={14} />
+                        Hesap Ekstresi
+                    </button>
                 </div>
             </div>
 
@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { Calendar } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { formatCommandError } from '../utils/commandError';
 import '../pages/MemberRegister.css'; // Reusing styles
 
 export default function CoopRegister() {
@@ -28,7 +29,7 @@ export default function CoopRegister() {
             setStartDate('');
         } catch (error) {
             console.error('Kayıt hatası:', error);
-            alert(`Kayıt başarısız: ${error}`);
+            alert(`Kayıt başarısız:\n${formatCommandError(error)}`);
         }
     };
 
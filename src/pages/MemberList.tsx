@@ -1,6 +1,8 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Search, Edit, Trash2, User } from 'lucide-react';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Search, Edit, Trash2, User, ChevronLeft, ChevronRight, FileText, Paperclip } from 'lucide-react';
+import { formatCommandError } from '../utils/commandError';
 import './MemberList.css';
 
 interface Member {
@@ -10,38 +12,113 @@ interface Member {
     phone_1: string;
     phone_2?: string;
     registration_date: string;
+    province?: string;
+    district?: string;
+    neighbourhood?: string;
+    address?: string;
+    email?: string;
+    iban?: string;
+    birth_date?: string;
+    occupation?: string;
+    notes?: string;
+    debt: number;
 }
 
+type ProfileField = 'province' | 'district' | 'neighbourhood' | 'address' | 'email' | 'iban' | 'birth_date' | 'occupation' | 'notes';
+
+const profileFields: { field: ProfileField; label: string; type?: string }[] = [
+    { field: 'province', label: 'İl' },
+    { field: 'district', label: 'İlçe' },
+    { field: 'neighbourhood', label: 'Mahalle' },
+    { field: 'address', label: 'Açık Adres' },
+    { field: 'email', label: 'E-posta' },
+    { field: 'iban', label: 'IBAN' },
+    { field: 'birth_date', label: 'Doğum Tarihi', type: 'date' },
+    { field: 'occupation', label: 'Meslek' },
+    { field: 'notes', label: 'Notlar' },
+];
+
+type DocumentKind = 'id_card' | 'application' | 'contract' | 'other';
+
+const documentKinds: { kind: DocumentKind; label: string }[] = [
+    { kind: 'id_card', label: 'Kimlik' },
+    { kind: 'application', label: 'Üyelik Başvurusu' },
+    { kind: 'contract', label: 'Sözleşme' },
+    { kind: 'other', label: 'Diğer' },
+];
+
+interface MemberDocument {
+    id: number;
+    member_id?: number;
+    coop_member_id?: number;
+    kind: DocumentKind;
+    file_name: string;
+    mime_type: string;
+    size: number;
+    note?: string;
+    created_at?: string;
+}
+
+interface MemberPage {
+    members: Member[];
+    total: number;
+    offset: number;
+    limit: number;
+}
+
+type SortKey = 'name' | 'registration_date' | 'debt';
+
+const PAGE_SIZE = 50;
+
 export default function MemberList() {
     const [members, setMembers] = useState<Member[]>([]);
+    const [total, setTotal] = useState(0);
+    const [offset, setOffset] = useState(0);
+    // Unset until a header is clicked. Searches always list the best matches first; the sort only orders ties.
+    const [sort, setSort] = useState<SortKey | null>(null);
+    const [descending, setDescending] = useState(false);
     const [searchQuery, setSearchQuery] = useState('');
     const [selectedMember, setSelectedMember] = useState<Member | null>(null);
     const [isEditing, setIsEditing] = useState(false);
     const [editForm, setEditForm] = useState<Partial<Member>>({});
+    const [documents, setDocuments] = useState<MemberDocument[]>([]);
+    const [documentKind, setDocumentKind] = useState<DocumentKind>('id_card');
 
     const fetchMembers = async () => {
         try {
-            const result = await invoke<Member[]>('get_members');
-            setMembers(result);
+            const result = await invoke<MemberPage>('list_members', {
+                query: {
+                    search: searchQuery || null,
+                    sort,
+                    descending,
+                    offset,
+                    limit: PAGE_SIZE
+                }
+            });
+            setMembers(result.members);
+            setTotal(result.total);
         } catch (error) {
             console.error('Üye listesi alınamadı:', error);
         }
     };
 
-    const handleSearch = async (query: string) => {
+    const handleSearch = (query: string) => {
         setSearchQuery(query);
-        if (query.length > 0) {
-            try {
-                const result = await invoke<Member[]>('search_members', { query });
-                setMembers(result);
-            } catch (error) {
-                console.error('Arama hatası:', error);
-            }
+        setOffset(0);
+    };
+
+    const handleSort = (key: SortKey) => {
+        if (key === sort) {
+            setDescending(!descending);
         } else {
-            fetchMembers();
+            setSort(key);
+            setDescending(key === 'debt');
         }
+        setOffset(0);
     };
 
+    const sortMark = (key: SortKey) => (key === sort ? (descending ? ' ▼' : ' ▲') : '');
+
     const handleEditClick = () => {
         setEditForm(selectedMember!);
         setIsEditing(true);
@@ -57,7 +134,8 @@ export default function MemberList() {
                     full_name: editForm.full_name || '',
                     phone_1: editForm.phone_1 || '',
                     phone_2: editForm.phone_2 || null,
-                    registration_date: editForm.registration_date || ''
+                    registration_date: editForm.registration_date || '',
+                    ...Object.fromEntries(profileFields.map(({ field }) => [field, editForm[field] || null]))
                 }
             });
             alert('Üye güncellendi!');
@@ -66,7 +144,7 @@ export default function MemberList() {
             fetchMembers(); // Refresh list
         } catch (error) {
             console.error('Güncelleme hatası:', error);
-            alert(`Güncelleme başarısız: ${error}`);
+            alert(`Güncelleme başarısız:\n${formatCommandError(error)}`);
         }
     };
 
@@ -74,9 +152,67 @@ export default function MemberList() {
         setEditForm(prev => ({ ...prev, [field]: value }));
     };
 
+    const fetchDocuments = async (memberId: number) => {
+        try {
+            setDocuments(await invoke<MemberDocument[]>('get_documents', { memberId, coopMemberId: null }));
+        } catch (error) {
+            console.error('Belgeler alınamadı:', error);
+        }
+    };
+
+    const handleAttachDocument = async () => {
+        if (!selectedMember) return;
+        const sourcePath = await open({
+            multiple: false,
+            filters: [{ name: 'Belge', extensions: ['pdf', 'jpg', 'jpeg', 'png', 'tif', 'tiff', 'doc', 'docx'] }]
+        });
+        if (!sourcePath) return;
+        try {
+            await invoke('attach_document', {
+                args: {
+                    member_id: selectedMember.id,
+                    coop_member_id: null,
+                    kind: documentKind,
+                    source_path: sourcePath,
+                    note: null
+                }
+            });
+            fetchDocuments(selectedMember.id);
+        } catch (error) {
+            console.error('Belge eklenemedi:', error);
+            alert(`Belge eklenemedi:\n${formatCommandError(error)}`);
+        }
+    };
+
+    const handleOpenDocument = async (id: number) => {
+        try {
+            await invoke('open_document', { id });
+        } catch (error) {
+            alert(`Belge açılamadı:\n${formatCommandError(error)}`);
+        }
+    };
+
+    const handleDeleteDocument = async (doc: MemberDocument) => {
+        if (!selectedMember || !confirm(`"${doc.file_name}" silinsin mi?`)) return;
+        try {
+            await invoke('delete_document', { id: doc.id });
+            fetchDocuments(selectedMember.id);
+        } catch (error) {
+            alert(`Belge silinemedi:\n${formatCommandError(error)}`);
+        }
+    };
+
+    useEffect(() => {
+        if (selectedMember) {
+            fetchDocuments(selectedMember.id);
+        } else {
+            setDocuments([]);
+        }
+    }, [selectedMember]);
+
     useEffect(() => {
         fetchMembers();
-    }, []);
+    }, [searchQuery, sort, descending, offset]);
 
     return (
         <div className="member-list-container">
@@ -86,7 +222,7 @@ export default function MemberList() {
                     <Search className="search-icon" size={18} />
                     <input
                         type="text"
-                        placeholder="İsim veya TC No ile ara..."
+                        placeholder="İsim, TC No veya telefon ile ara..."
                         value={searchQuery}
                         onChange={(e) => handleSearch(e.target.value)}
                         className="search-input"
@@ -98,17 +234,18 @@ export default function MemberList() {
                 <table className="members-table">
                     <thead>
                         <tr>
-                            <th>İsim Soyisim</th>
+                            <th className="cursor-pointer" onClick={() => handleSort('name')}>İsim Soyisim{sortMark('name')}</th>
                             <th>TC No</th>
                             <th>Telefon</th>
-                            <th>Kayıt Tarihi</th>
+                            <th className="cursor-pointer" onClick={() => handleSort('registration_date')}>Kayıt Tarihi{sortMark('registration_date')}</th>
+                            <th className="cursor-pointer" onClick={() => handleSort('debt')}>Borç{sortMark('debt')}</th>
                             <th>İşlemler</th>
                         </tr>
                     </thead>
                     <tbody>
                         {members.length === 0 ? (
                             <tr>
-                                <td colSpan={5} className="text-center p-4 text-muted">Arama kriterlerine uygun üye bulunamadı.</td>
+                                <td colSpan={6} className="text-center p-4 text-muted">Arama kriterlerine uygun üye bulunamadı.</td>
                             </tr>
                         ) : (
                             members.map(member => (
@@ -117,6 +254,7 @@ export default function MemberList() {
                                     <td>{member.tc_number}</td>
                                     <td>{member.phone_1}</td>
                                     <td>{member.registration_date}</td>
+                                    <td>{member.debt.toLocaleString('tr-TR', { minimumFractionDigits: 2 })} ₺</td>
                                     <td>
                                         <button className="icon-btn" title="Düzenle">
                                             <Edit size={16} />
@@ -127,6 +265,19 @@ export default function MemberList() {
                         )}
                     </tbody>
                 </table>
+                <div className="flex justify-between items-center p-4">
+                    <span className="text-muted">
+                        {total === 0 ? '0 üye' : `${offset + 1}-${Math.min(offset + PAGE_SIZE, total)} / ${total} üye`}
+                    </span>
+                    <div className="flex gap-2">
+                        <button className="icon-btn" disabled={offset === 0} onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))} title="Önceki sayfa">
+                            <ChevronLeft size={18} />
+                        </button>
+                        <button className="icon-btn" disabled={offset + PAGE_SIZE >= total} onClick={() => setOffset(offset + PAGE_SIZE)} title="Sonraki sayfa">
+                            <ChevronRight size={18} />
+                        </button>
+                    </div>
+                </div>
             </div>
 
             {selectedMember && (
@@ -176,6 +327,16 @@ export default function MemberList() {
                                         value={editForm.registration_date || ''}
                                         onChange={e => handleInputChange('registration_date', e.target.value)}
                                     />
+                                    {profileFields.map(({ field, label, type }) => (
+                                        <input
+                                            key={field}
+                                            type={type ?? 'text'}
+                                            className="form-input"
+                                            value={editForm[field] || ''}
+                                            onChange={e => handleInputChange(field, e.target.value)}
+                                            placeholder={label}
+                                        />
+                                    ))}
                                 </div>
                             ) : (
                                 <>
@@ -195,6 +356,48 @@ export default function MemberList() {
                                         <span className="label">Kayıt Tarihi:</span>
                                         <span className="value">{selectedMember.registration_date}</span>
                                     </div>
+                                    {profileFields.map(({ field, label }) => (
+                                        <div className="detail-row" key={field}>
+                                            <span className="label">{label}:</span>
+                                            <span className="value">{selectedMember[field] || '-'}</span>
+                                        </div>
+                                    ))}
+                                    <div className="documents-section">
+                                        <div className="documents-header">
+                                            <h4>Belgeler</h4>
+                                            <div className="flex gap-2">
+                                                <select
+                                                    className="form-input"
+                                                    value={documentKind}
+                                                    onChange={e => setDocumentKind(e.target.value as DocumentKind)}
+                                                >
+                                                    {documentKinds.map(({ kind, label }) => (
+                                                        <option key={kind} value={kind}>{label}</option>
+                                                    ))}
+                                                </select>
+                                                <button className="icon-btn" onClick={handleAttachDocument} title="Belge Ekle">
+                                                    <Paperclip size={16} /> Ekle
+                                                </button>
+                                            </div>
+                                        </div>
+                                        {documents.length === 0 ? (
+                                            <div className="text-muted">Eklenmiş belge yok.</div>
+                                        ) : documents.map(doc => (
+                                            <div className="detail-row document-row" key={doc.id}>
+                                                <span className="label">
+                                                    {documentKinds.find(k => k.kind === doc.kind)?.label}
+                                                    {doc.coop_member_id ? ' (üyelik)' : ''}
+                                                </span>
+                                                <button className="link-btn value" onClick={() => handleOpenDocument(doc.id)} title="Aç">
+                                                    <FileText size={14} /> {doc.file_name}
+                                                </button>
+                                                <small className="text-muted">{(doc.size / 1024).toFixed(0)} KB</small>
+                                                <button className="btn-danger" onClick={() => handleDeleteDocument(doc)} title="Sil">
+                                                    <Trash2 size={14} />
+                                                </button>
+                                            </div>
+                                        ))}
+                                    </div>
                                 </>
                             )}
                         </div>
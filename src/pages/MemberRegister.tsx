@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { Calendar } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { formatCommandError } from '../utils/commandError';
 import './MemberRegister.css';
 
 interface MemberForm {
@@ -9,18 +10,38 @@ interface MemberForm {
     phone1: string;
     phone2: string;
     registrationDate: string;
+    province: string;
+    district: string;
+    neighbourhood: string;
+    address: string;
+    email: string;
+    iban: string;
+    birthDate: string;
+    occupation: string;
+    notes: string;
 }
 
+const emptyForm: MemberForm = {
+    tcNumber: '',
+    fullName: '',
+    phone1: '',
+    phone2: '',
+    registrationDate: '',
+    province: '',
+    district: '',
+    neighbourhood: '',
+    address: '',
+    email: '',
+    iban: '',
+    birthDate: '',
+    occupation: '',
+    notes: ''
+};
+
 export default function MemberRegister() {
-    const [formData, setFormData] = useState<MemberForm>({
-        tcNumber: '',
-        fullName: '',
-        phone1: '',
-        phone2: '',
-        registrationDate: ''
-    });
-
-    const handleChange = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const [formData, setFormData] = useState<MemberForm>(emptyForm);
+
+    const handleChange = (e: React.ChangeEvent<HTMLInputElement | HTMLTextAreaElement>) => {
         const { name, value } = e.target;
 
         // TC Number Validation: Only numbers, max 11 chars
@@ -52,22 +73,25 @@ export default function MemberRegister() {
                     full_name: formData.fullName,
                     phone_1: formData.phone1,
                     phone_2: formData.phone2 || null, // Handle optional field
-                    registration_date: formData.registrationDate
+                    registration_date: formData.registrationDate,
+                    province: formData.province || null,
+                    district: formData.district || null,
+                    neighbourhood: formData.neighbourhood || null,
+                    address: formData.address || null,
+                    email: formData.email || null,
+                    iban: formData.iban || null,
+                    birth_date: formData.birthDate || null,
+                    occupation: formData.occupation || null,
+                    notes: formData.notes || null
                 }
             });
 
             alert('Üye başarıyla kaydedildi!');
             // Reset form
-            setFormData({
-                tcNumber: '',
-                fullName: '',
-                phone1: '',
-                phone2: '',
-                registrationDate: ''
-            });
+            setFormData(emptyForm);
         } catch (error) {
             console.error('Kayıt hatası:', error);
-            alert(`Kayıt başarısız: ${error}`);
+            alert(`Kayıt başarısız:\n${formatCommandError(error)}`);
         }
     };
 
@@ -165,6 +189,112 @@ export default function MemberRegister() {
                             </button>
                         </div>
                     </div>
+
+                    {/* Address */}
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="province">İl</label>
+                        <input
+                            type="text"
+                            id="province"
+                            name="province"
+                            className="form-input"
+                            value={formData.province}
+                            onChange={handleChange}
+                        />
+                    </div>
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="district">İlçe</label>
+                        <input
+                            type="text"
+                            id="district"
+                            name="district"
+                            className="form-input"
+                            value={formData.district}
+                            onChange={handleChange}
+                        />
+                    </div>
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="neighbourhood">Mahalle</label>
+                        <input
+                            type="text"
+                            id="neighbourhood"
+                            name="neighbourhood"
+                            className="form-input"
+                            value={formData.neighbourhood}
+                            onChange={handleChange}
+                        />
+                    </div>
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="occupation">Meslek</label>
+                        <input
+                            type="text"
+                            id="occupation"
+                            name="occupation"
+                            className="form-input"
+                            value={formData.occupation}
+                            onChange={handleChange}
+                        />
+                    </div>
+                    <div className="form-group full-width">
+                        <label className="form-label" htmlFor="address">Açık Adres</label>
+                        <textarea
+                            id="address"
+                            name="address"
+                            className="form-input"
+                            value={formData.address}
+                            onChange={handleChange}
+                            rows={2}
+                        />
+                    </div>
+
+                    {/* Contact and payment */}
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="email">E-posta</label>
+                        <input
+                            type="email"
+                            id="email"
+                            name="email"
+                            className="form-input"
+                            value={formData.email}
+                            onChange={handleChange}
+                            placeholder="ornek@eposta.com"
+                        />
+                    </div>
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="iban">IBAN</label>
+                        <input
+                            type="text"
+                            id="iban"
+                            name="iban"
+                            className="form-input"
+                            value={formData.iban}
+                            onChange={handleChange}
+                            placeholder="TR00 0000 0000 0000 0000 0000 00"
+                        />
+                    </div>
+                    <div className="form-group">
+                        <label className="form-label" htmlFor="birthDate">Doğum Tarihi</label>
+                        <input
+                            type="date"
+                            id="birthDate"
+                            name="birthDate"
+                            className="form-input"
+                            value={formData.birthDate}
+                            onChange={handleChange}
+                        />
+                    </div>
+
+                    <div className="form-group full-width">
+                        <label className="form-label" htmlFor="notes">Notlar</label>
+                        <textarea
+                            id="notes"
+                            name="notes"
+                            className="form-input"
+                            value={formData.notes}
+                            onChange={handleChange}
+                            rows={3}
+                        />
+                    </div>
                 </div>
 
                 <div className="form-actions">
@@ -0,0 +1,47 @@
+export interface FieldError {
+    field: string;
+    code: string;
+    message: string;
+    message_tr: string;
+}
+
+export interface CommandError {
+    kind: 'not_found' | 'validation' | 'invalid' | 'conflict' | 'database' | 'file';
+    code: string;
+    message: string;
+    message_tr: string;
+    fields?: FieldError[];
+}
+
+const fieldLabels: Record<string, string> = {
+    tc_number: 'TC Kimlik No',
+    full_name: 'İsim Soyisim',
+    phone_1: 'Telefon 1',
+    phone_2: 'Telefon 2',
+    name: 'Kooperatif Adı',
+    email: 'E-posta',
+    iban: 'IBAN',
+    birth_date: 'Doğum Tarihi',
+};
+
+export function isCommandError(error: unknown): error is CommandError {
+    return typeof error === 'object' && error !== null && 'kind' in error && 'code' in error;
+}
+
+/** Returns the stable error code of a command error, e.g. `tc_taken`. */
+export function commandErrorCode(error: unknown): string | undefined {
+    return isCommandError(error) ? error.code : undefined;
+}
+
+/** Turns an error thrown by `invoke` into a message for the user. */
+export function formatCommandError(error: unknown): string {
+    if (isCommandError(error)) {
+        if (error.fields) {
+            return error.fields
+                .map(f => `${fieldLabels[f.field] ?? f.field}: ${f.message_tr}`)
+                .join('\n');
+        }
+        return error.message_tr;
+    }
+    return String(error);
+}